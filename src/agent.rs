@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+
+use crate::algebra::Point2f;
+use crate::game_rules::GameRules;
+use crate::key_binding::Action as KeyAction;
+use crate::record::{Params, Record};
+use crate::session::Session;
+
+// key ids driven by agents, in the order of Action::keys
+const AGENT_KEYS: [KeyAction; 6] = [
+	KeyAction::Left,
+	KeyAction::Up,
+	KeyAction::Right,
+	KeyAction::Down,
+	KeyAction::Slowdown,
+	KeyAction::Fire,
+];
+
+pub fn point(p: Point2f) -> [f32; 2] {
+	[p.x, p.y]
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CircleObservation {
+	pub p: [f32; 2],
+	pub r: f32,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EnemyObservation {
+	pub p: [f32; 2],
+	// absolute positions
+	pub hitboxes: Vec<CircleObservation>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BulletObservation {
+	pub p: [f32; 2],
+	// per second of scaled time
+	pub v: [f32; 2],
+	pub r: f32,
+}
+
+// what an agent sees after each tick, positions are in window coordinates
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Observation {
+	// unscaled game time
+	pub time: f32,
+	pub player: CircleObservation,
+	pub enemies: Vec<EnemyObservation>,
+	pub enemy_bullets: Vec<BulletObservation>,
+	// seconds of quick and slow pools
+	pub slowdown_reserves: [f32; 2],
+	pub slowing: bool,
+	pub difficulty: f32,
+	pub hits: u32,
+}
+
+// keys held by an agent in a tick
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Action {
+	// bit i is direction key i: left, up, right, down
+	pub directions: u8,
+	pub fire: bool,
+	pub slowdown: bool,
+}
+
+impl Action {
+	// "<directions> <fire> <slowdown>", e.g. "5 1 0"
+	pub fn from_str(line: &str) -> Result<Action, String> {
+		let args = line
+			.split_whitespace()
+			.map(|x| x.parse::<u8>().map_err(|e| e.to_string()))
+			.collect::<Result<Vec<u8>, String>>()?;
+		if args.len() != 3 {
+			return Err("action takes 3 args".to_string());
+		}
+		Ok(Action {
+			directions: args[0],
+			fire: args[1] != 0,
+			slowdown: args[2] != 0,
+		})
+	}
+
+	fn keys(&self) -> [bool; 6] {
+		[
+			self.directions & 1 != 0,
+			self.directions & 2 != 0,
+			self.directions & 4 != 0,
+			self.directions & 8 != 0,
+			self.slowdown,
+			self.fire,
+		]
+	}
+}
+
+// headless session stepped with fixed dt, recorded as a normal replay
+pub struct AgentSession {
+	session: Session,
+	dt: f32,
+	action: Action,
+}
+
+impl AgentSession {
+	pub fn new(params: Params, rules: GameRules, dt: f32) -> AgentSession {
+		let mut session = Session::new(params, rules, None);
+		session.set_headless(true);
+		AgentSession {
+			session,
+			dt,
+			action: Default::default(),
+		}
+	}
+
+	pub fn observe(&self) -> Observation {
+		self.session.observation()
+	}
+
+	// keys are sent only on change, as a player would press them
+	pub fn step(&mut self, action: Action) -> Observation {
+		let old_keys = self.action.keys();
+		for ((key, old), new) in AGENT_KEYS
+			.iter()
+			.zip(old_keys.iter())
+			.zip(action.keys().iter())
+		{
+			if old != new {
+				self.session.proc_key(key.key_id().unwrap(), *new);
+			}
+		}
+		self.action = action;
+		self.session.tick(self.dt);
+		self.observe()
+	}
+
+	// replay stops at the last operation,
+	// so all keys are released after the last tick to replay the whole episode
+	pub fn finish(&mut self) -> &Record {
+		for key in AGENT_KEYS.iter() {
+			self.session.proc_key(key.key_id().unwrap(), false);
+		}
+		self.action = Default::default();
+		self.session.get_record()
+	}
+
+	pub fn steps(&self) -> usize {
+		self.session.get_record().dt_seq.len()
+	}
+
+	pub fn seed(&self) -> u64 {
+		self.session.get_record().params.seed
+	}
+
+	// replay and stats
+	pub fn save(&mut self, filename: String) {
+		self.finish();
+		self.session.save(filename);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_agent_session() {
+		let params = Params {
+			seed: 1,
+			..Default::default()
+		};
+		let mut agent_session = AgentSession::new(params.clone(), Default::default(), 1. / 60.);
+		let start = agent_session.observe().player.p;
+		let action = Action {
+			directions: 1,
+			fire: true,
+			slowdown: false,
+		};
+		let mut observations = Vec::new();
+		for _ in 0..30 {
+			observations.push(agent_session.step(action));
+		}
+		// moved left
+		assert!(observations[29].player.p[0] < start[0]);
+		assert_eq!(agent_session.finish().dt_seq.len(), 30);
+		// same actions, same observations
+		let mut agent_session2 = AgentSession::new(params, Default::default(), 1. / 60.);
+		for observation in observations.iter() {
+			assert_eq!(&agent_session2.step(action), observation);
+		}
+	}
+}
@@ -0,0 +1,77 @@
+use std::io::{BufRead, Write};
+
+use crate::agent::{Action, AgentSession};
+use crate::file_manager::FileManager;
+use crate::game_rules::GameRules;
+use crate::record::Params;
+
+// empty episodes are not saved, episode is counted in this run
+// so that episodes of one seed never overwrite each other
+fn save_episode(agent_session: &mut AgentSession, file_manager: &FileManager, episode: &mut u32) {
+	if agent_session.steps() > 0 {
+		agent_session.save(file_manager.get_agent_replay_path(*episode, agent_session.seed()));
+		*episode += 1;
+	}
+}
+
+fn parse_action(line: &str) -> Result<Action, String> {
+	if line.starts_with('{') {
+		serde_json::from_str(line).map_err(|e| e.to_string())
+	} else {
+		Action::from_str(line)
+	}
+}
+
+// one line in, one line out:
+// an action(json or compact text) steps the session,
+// "reset <seed>" starts a new episode, "quit" exits,
+// the reply is an observation, or {"error": ...} on bad input
+pub fn run(params: Params, rules: GameRules, dt: f32) {
+	let file_manager = FileManager::new();
+	let mut episode = 0;
+	let mut agent_session = AgentSession::new(params.clone(), rules.clone(), dt);
+	let stdin = std::io::stdin();
+	let stdout = std::io::stdout();
+	let mut out = stdout.lock();
+	let mut reply = serde_json::to_string(&agent_session.observe()).unwrap();
+	loop {
+		// client closing the pipe ends the episode like eof
+		if writeln!(out, "{}", reply)
+			.and_then(|_| out.flush())
+			.is_err()
+		{
+			break;
+		}
+		let mut line = String::new();
+		if stdin.lock().read_line(&mut line).unwrap() == 0 {
+			break;
+		}
+		let line = line.trim();
+		if line == "quit" {
+			break;
+		}
+		reply = if let Some(seed) = line.strip_prefix("reset") {
+			match seed.trim().parse::<u64>() {
+				Ok(seed) => {
+					save_episode(&mut agent_session, &file_manager, &mut episode);
+					agent_session = AgentSession::new(
+						Params {
+							seed,
+							..params.clone()
+						},
+						rules.clone(),
+						dt,
+					);
+					serde_json::to_string(&agent_session.observe()).unwrap()
+				}
+				Err(e) => serde_json::json!({ "error": e.to_string() }).to_string(),
+			}
+		} else {
+			match parse_action(line) {
+				Ok(action) => serde_json::to_string(&agent_session.step(action)).unwrap(),
+				Err(e) => serde_json::json!({ "error": e }).to_string(),
+			}
+		};
+	}
+	save_episode(&mut agent_session, &file_manager, &mut episode);
+}
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+// all sounds are mono f32 pcm at this rate
+pub const SAMPLE_RATE: i32 = 44100;
+// when exceeded, a voice of lowest priority and least remaining samples is dropped
+const MAX_VOICES: usize = 32;
+
+pub type Samples = Arc<Vec<f32>>;
+
+struct Voice {
+	samples: Samples,
+	position: usize,
+	gain: f32,
+	priority: u8,
+}
+
+impl Voice {
+	fn remain(&self) -> usize {
+		self.samples.len() - self.position
+	}
+}
+
+// software mixer, no device needed
+pub struct Mixer {
+	voices: VecDeque<Voice>,
+	volume: f32,
+}
+
+impl Mixer {
+	pub fn new(volume: f32) -> Mixer {
+		Mixer {
+			voices: VecDeque::new(),
+			volume,
+		}
+	}
+
+	// short frequent sounds should have low priority,
+	// so that they never cut music or long sounds
+	pub fn play(&mut self, samples: Samples, gain: f32, priority: u8) {
+		if self.voices.len() >= MAX_VOICES {
+			let (index, voice) = self
+				.voices
+				.iter()
+				.enumerate()
+				.min_by_key(|(_, voice)| (voice.priority, voice.remain()))
+				.unwrap();
+			// new sound is the least important one
+			if (voice.priority, voice.remain()) > (priority, samples.len()) {
+				return;
+			}
+			self.voices.remove(index);
+		}
+		self.voices.push_back(Voice {
+			samples,
+			position: 0,
+			gain,
+			priority,
+		});
+	}
+
+	// overwrite buffer with next samples of all voices
+	pub fn render(&mut self, buffer: &mut [f32]) {
+		for sample in buffer.iter_mut() {
+			*sample = 0.;
+		}
+		for voice in self.voices.iter_mut() {
+			let remain = &voice.samples[voice.position..];
+			let len = remain.len().min(buffer.len());
+			for (sample, voice_sample) in buffer.iter_mut().zip(remain[..len].iter()) {
+				*sample += voice_sample * voice.gain;
+			}
+			voice.position += len;
+		}
+		self.voices
+			.retain(|voice| voice.position < voice.samples.len());
+		for sample in buffer.iter_mut() {
+			*sample = (*sample * self.volume).clamp(-1., 1.);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_mixer() {
+		let mut mixer = Mixer::new(1.);
+		mixer.play(Arc::new(vec![0.5; 3]), 1., 0);
+		mixer.play(Arc::new(vec![0.25; 5]), 2., 0);
+		let mut buffer = [1.; 4];
+		mixer.render(&mut buffer);
+		assert_eq!(buffer, [1., 1., 1., 0.5]);
+		mixer.render(&mut buffer);
+		assert_eq!(buffer, [0.5, 0., 0., 0.]);
+		assert!(mixer.voices.is_empty());
+
+		// full of long high priority voices
+		for _ in 0..MAX_VOICES - 1 {
+			mixer.play(Arc::new(vec![0.; 100]), 1., 1);
+		}
+		mixer.play(Arc::new(vec![0.; 10]), 1., 0);
+		// short low priority voice is dropped first
+		mixer.play(Arc::new(vec![0.; 50]), 1., 0);
+		assert_eq!(mixer.voices.len(), MAX_VOICES);
+		assert!(mixer.voices.iter().all(|voice| voice.samples.len() != 10));
+		// lower than all voices, not played
+		mixer.play(Arc::new(vec![0.; 10]), 1., 0);
+		assert!(mixer.voices.iter().all(|voice| voice.samples.len() != 10));
+		// evicts the low priority one
+		mixer.play(Arc::new(vec![0.; 10]), 1., 2);
+		assert!(mixer.voices.iter().all(|voice| voice.priority > 0));
+	}
+}
@@ -0,0 +1,229 @@
+mod mixer;
+pub mod music;
+pub mod synth;
+
+pub use mixer::{Mixer, Samples, SAMPLE_RATE};
+pub use music::{Music, MusicStep};
+pub use synth::Sound;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+
+use crate::cannon::CannonType;
+
+// rendered cannon sounds kept by audio backend, cleared when full
+const CANNON_SOUND_CACHE: usize = 128;
+
+// what a cannon sound is synthesized from,
+// gameplay only describes sounds and audio backend renders them
+#[derive(Clone, Debug, PartialEq)]
+pub struct CannonSound {
+	pub cannon_type: CannonType,
+	pub parameters: Vec<(&'static str, f32)>,
+}
+
+impl CannonSound {
+	fn key(&self) -> (&'static str, Vec<u32>) {
+		(
+			self.cannon_type.name(),
+			self.parameters
+				.iter()
+				.map(|(_, value)| value.to_bits())
+				.collect(),
+		)
+	}
+
+	pub fn render(&self) -> Samples {
+		Arc::new(synth::cannon_sound(self.cannon_type, &self.parameters).render())
+	}
+}
+
+// emitted by session, consumed by audio backend
+#[derive(Clone, Debug)]
+pub enum SoundEvent {
+	Fire,
+	EnemyDestroyed,
+	PlayerHit,
+	LevelUp,
+	LevelDown,
+	SlowdownStart,
+	SlowdownStop,
+	// synthesized from cannon parameters
+	Cannon(Arc<CannonSound>),
+	// a step of background music
+	Music(MusicStep),
+}
+
+pub const MUSIC_GAIN: f32 = 0.3;
+
+// events with fixed sounds
+pub const SOUND_EVENTS: [SoundEvent; 7] = [
+	SoundEvent::Fire,
+	SoundEvent::EnemyDestroyed,
+	SoundEvent::PlayerHit,
+	SoundEvent::LevelUp,
+	SoundEvent::LevelDown,
+	SoundEvent::SlowdownStart,
+	SoundEvent::SlowdownStop,
+];
+
+impl SoundEvent {
+	pub fn name(&self) -> &'static str {
+		match self {
+			SoundEvent::Fire => "fire",
+			SoundEvent::EnemyDestroyed => "enemy_destroyed",
+			SoundEvent::PlayerHit => "player_hit",
+			SoundEvent::LevelUp => "level_up",
+			SoundEvent::LevelDown => "level_down",
+			SoundEvent::SlowdownStart => "slowdown_start",
+			SoundEvent::SlowdownStop => "slowdown_stop",
+			SoundEvent::Cannon(_) => "cannon",
+			SoundEvent::Music(_) => "music",
+		}
+	}
+
+	// synth strings, see synth::Sound
+	pub fn sound_strs(&self) -> Vec<&'static str> {
+		match self {
+			SoundEvent::Fire => vec![
+				"sqr 1600 1000 0.001 0.03 0.3",
+				"noi 8000 8000 0.001 0.02 0.2",
+			],
+			SoundEvent::EnemyDestroyed => {
+				vec!["noi 3000 300 0.001 0.25 0.6", "sin 180 50 0.001 0.2 0.5"]
+			}
+			SoundEvent::PlayerHit => {
+				vec!["saw 220 40 0.001 0.4 0.5", "noi 1500 200 0.001 0.3 0.4"]
+			}
+			SoundEvent::LevelUp => vec![
+				"tri 523 523 0.005 0.08 0.4",
+				"tri 659 659 0.005 0.08 0.4 0.06",
+				"tri 784 784 0.005 0.15 0.4 0.12",
+			],
+			SoundEvent::LevelDown => vec![
+				"tri 784 784 0.005 0.08 0.4",
+				"tri 659 659 0.005 0.08 0.4 0.06",
+				"tri 523 523 0.005 0.15 0.4 0.12",
+			],
+			SoundEvent::SlowdownStart => {
+				vec!["sin 600 150 0.02 0.35 0.4", "noi 800 100 0.05 0.3 0.1"]
+			}
+			SoundEvent::SlowdownStop => vec!["sin 150 600 0.02 0.3 0.4"],
+			SoundEvent::Cannon(_) | SoundEvent::Music(_) => Vec::new(),
+		}
+	}
+
+	pub fn samples(&self) -> Samples {
+		match self {
+			SoundEvent::Cannon(sound) => sound.render(),
+			SoundEvent::Music(step) => step.render(),
+			_ => Arc::new(Sound::from_strs(&self.sound_strs()).unwrap().render()),
+		}
+	}
+
+	// music is never cut by effects, fire and cannons are the most frequent
+	fn priority(&self) -> u8 {
+		match self {
+			SoundEvent::Music(_) => 2,
+			SoundEvent::Fire | SoundEvent::Cannon(_) => 0,
+			_ => 1,
+		}
+	}
+
+	fn gain(&self) -> f32 {
+		match self {
+			// fired in every few ticks
+			SoundEvent::Fire => 0.1,
+			SoundEvent::Cannon(_) => 0.15,
+			SoundEvent::Music(_) => MUSIC_GAIN,
+			_ => 0.5,
+		}
+	}
+}
+
+struct MixerCallback {
+	mixer: Mixer,
+}
+
+impl AudioCallback for MixerCallback {
+	type Channel = f32;
+
+	fn callback(&mut self, out: &mut [f32]) {
+		self.mixer.render(out);
+	}
+}
+
+// silent if no audio device is available
+pub struct Audio {
+	device: Option<AudioDevice<MixerCallback>>,
+	sounds: HashMap<&'static str, Samples>,
+	cannon_sounds: HashMap<(&'static str, Vec<u32>), Samples>,
+}
+
+impl Audio {
+	pub fn new(sdl_context: &sdl2::Sdl, volume: f32) -> Audio {
+		let spec = AudioSpecDesired {
+			freq: Some(SAMPLE_RATE),
+			channels: Some(1),
+			samples: Some(1024),
+		};
+		let device = sdl_context
+			.audio()
+			.and_then(|audio_subsystem| {
+				audio_subsystem.open_playback(None, &spec, |_| MixerCallback {
+					mixer: Mixer::new(volume),
+				})
+			})
+			.map_err(|e| println!("Failed to open audio device: {}", e))
+			.ok();
+		if let Some(device) = device.as_ref() {
+			device.resume();
+		}
+		Audio {
+			device,
+			sounds: SOUND_EVENTS
+				.iter()
+				.map(|event| (event.name(), event.samples()))
+				.collect(),
+			cannon_sounds: HashMap::new(),
+		}
+	}
+
+	// a cannon fires many times, so its sound is rendered once
+	fn samples(&mut self, event: &SoundEvent) -> Samples {
+		match event {
+			SoundEvent::Cannon(sound) => {
+				let key = sound.key();
+				if let Some(samples) = self.cannon_sounds.get(&key) {
+					return samples.clone();
+				}
+				if self.cannon_sounds.len() >= CANNON_SOUND_CACHE {
+					self.cannon_sounds.clear();
+				}
+				let samples = sound.render();
+				self.cannon_sounds.insert(key, samples.clone());
+				samples
+			}
+			SoundEvent::Music(step) => step.render(),
+			_ => self.sounds[event.name()].clone(),
+		}
+	}
+
+	// nothing is rendered without a device
+	pub fn play(&mut self, events: &[SoundEvent]) {
+		if self.device.is_none() || events.is_empty() {
+			return;
+		}
+		// rendered before locking, so that the audio thread is not blocked
+		let voices: Vec<(Samples, f32, u8)> = events
+			.iter()
+			.map(|event| (self.samples(event), event.gain(), event.priority()))
+			.collect();
+		let mut callback = self.device.as_mut().unwrap().lock();
+		for (samples, gain, priority) in voices.into_iter() {
+			callback.mixer.play(samples, gain, priority);
+		}
+	}
+}
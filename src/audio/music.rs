@@ -0,0 +1,194 @@
+use rand::Rng;
+use rand::SeedableRng;
+
+use super::mixer::{Mixer, Samples, SAMPLE_RATE};
+use super::synth::{low_pass, Sound};
+use super::MUSIC_GAIN;
+
+const STEPS_PER_BAR: usize = 16;
+const SCALES: [[i32; 7]; 3] = [
+	[0, 2, 3, 5, 7, 8, 10], // aeolian
+	[0, 2, 3, 5, 7, 9, 10], // dorian
+	[0, 1, 3, 5, 7, 8, 10], // phrygian
+];
+// scale degrees of chord roots, one chord per bar
+const PROGRESSIONS: [[i32; 4]; 4] = [[0, 5, 2, 6], [0, 3, 4, 0], [0, 6, 5, 4], [0, 3, 6, 4]];
+// chord tones of arpeggio, in scale degrees
+const ARPEGGIO: [i32; 4] = [0, 2, 4, 7];
+
+// difficulty thresholds of layers
+const KICK_DIFFICULTY: f32 = 0.2;
+const ARPEGGIO_DIFFICULTY: f32 = 0.35;
+const HAT_DIFFICULTY: f32 = 0.5;
+const MELODY_DIFFICULTY: f32 = 0.6;
+const FAST_ARPEGGIO_DIFFICULTY: f32 = 0.8;
+
+// low-pass cutoff at normal speed and full slowdown
+const CUTOFF_NORMAL: f32 = 8000.;
+const CUTOFF_SLOW: f32 = 500.;
+
+// synth strings and low-pass cutoff of a step,
+// rendered by audio backend so that headless runs skip synthesis
+#[derive(Clone, Debug, PartialEq)]
+pub struct MusicStep {
+	strs: Vec<String>,
+	cutoff: f32,
+}
+
+impl MusicStep {
+	pub fn render(&self) -> Samples {
+		let mut samples = Sound::from_strs(&self.strs).unwrap().render();
+		low_pass(&mut samples, self.cutoff);
+		Samples::new(samples)
+	}
+}
+
+// procedural music, stepped by scaled game time,
+// so it is deterministic in replays
+pub struct Music {
+	rng: rand_pcg::Pcg64Mcg,
+	root: f32,
+	scale: [i32; 7],
+	progression: [i32; 4],
+	// sixteenth notes since start
+	step: usize,
+	timer: f32,
+	melody_degree: i32,
+}
+
+impl Music {
+	pub fn new(seed: u64) -> Music {
+		let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(seed);
+		// A2 to E3
+		let root = 110. * 2f32.powf(rng.gen_range(0, 8) as f32 / 12.);
+		let scale = SCALES[rng.gen_range(0, SCALES.len())];
+		let progression = PROGRESSIONS[rng.gen_range(0, PROGRESSIONS.len())];
+		Music {
+			rng,
+			root,
+			scale,
+			progression,
+			step: 0,
+			timer: 0.,
+			melody_degree: 7,
+		}
+	}
+
+	fn freq(&self, degree: i32) -> f32 {
+		let semitone = self.scale[degree.rem_euclid(7) as usize] + degree.div_euclid(7) * 12;
+		self.root * 2f32.powf(semitone as f32 / 12.)
+	}
+
+	// 80 bpm at difficulty 0, 180 at 1
+	fn step_time(difficulty: f32) -> f32 {
+		let bpm = 80. + difficulty.clamp(0., 1.) * 100.;
+		60. / bpm / 4.
+	}
+
+	// synth strings of current step, pitch scales all frequencies
+	fn step_strs(&mut self, difficulty: f32, pitch: f32) -> Vec<String> {
+		let beat = self.step % STEPS_PER_BAR;
+		let chord = self.progression[self.step / STEPS_PER_BAR % self.progression.len()];
+		let mut strs = Vec::new();
+		if beat.is_multiple_of(8) {
+			let freq = self.freq(chord - 14) * pitch;
+			strs.push(format!("tri {} {} 0.005 0.4 0.5", freq, freq));
+		}
+		if difficulty >= KICK_DIFFICULTY && beat.is_multiple_of(4) {
+			strs.push(format!(
+				"sin {} {} 0.001 0.15 0.5",
+				150. * pitch,
+				40. * pitch
+			));
+		}
+		let arpeggio_interval = if difficulty >= FAST_ARPEGGIO_DIFFICULTY {
+			1
+		} else {
+			2
+		};
+		if difficulty >= ARPEGGIO_DIFFICULTY && beat.is_multiple_of(arpeggio_interval) {
+			let tone = ARPEGGIO[beat / arpeggio_interval % ARPEGGIO.len()];
+			let freq = self.freq(chord + tone) * pitch;
+			strs.push(format!("sqr {} {} 0.002 0.1 0.08", freq, freq));
+		}
+		if difficulty >= HAT_DIFFICULTY && beat % 4 == 2 {
+			strs.push(format!(
+				"noi {} {} 0.001 0.04 0.1",
+				9000. * pitch,
+				7000. * pitch
+			));
+		}
+		// random walk around the chord
+		if difficulty >= MELODY_DIFFICULTY && beat.is_multiple_of(2) && self.rng.gen::<f32>() < 0.4
+		{
+			self.melody_degree = (self.melody_degree + self.rng.gen_range(-2, 3)).clamp(3, 12);
+			let freq = self.freq(chord + self.melody_degree) * pitch;
+			strs.push(format!("saw {} {} 0.01 0.25 0.1", freq, freq));
+		}
+		strs
+	}
+
+	// shift is slowdown transition from 0 to 1, as in background,
+	// returns steps passed, state always advances even if they are not played
+	pub fn tick(&mut self, dt: f32, difficulty: f32, shift: f32) -> Vec<MusicStep> {
+		let mut result = Vec::new();
+		self.timer += dt;
+		let step_time = Music::step_time(difficulty);
+		while self.timer >= step_time {
+			self.timer -= step_time;
+			// an octave down at full slowdown
+			let strs = self.step_strs(difficulty, 0.5f32.powf(shift));
+			self.step += 1;
+			if strs.is_empty() {
+				continue;
+			}
+			result.push(MusicStep {
+				strs,
+				cutoff: CUTOFF_NORMAL * (CUTOFF_SLOW / CUTOFF_NORMAL).powf(shift),
+			});
+		}
+		result
+	}
+}
+
+// render music of duration seconds through a mixer, for preview
+pub fn render_music(seed: u64, difficulty: f32, shift: f32, duration: f32) -> Vec<f32> {
+	const CHUNK: usize = SAMPLE_RATE as usize / 100;
+	let mut music = Music::new(seed);
+	let mut mixer = Mixer::new(1.);
+	let mut buffer = vec![0.; (duration * SAMPLE_RATE as f32) as usize];
+	for chunk in buffer.chunks_mut(CHUNK) {
+		for step in music
+			.tick(CHUNK as f32 / SAMPLE_RATE as f32, difficulty, shift)
+			.iter()
+		{
+			mixer.play(step.render(), MUSIC_GAIN, 0);
+		}
+		mixer.render(chunk);
+	}
+	buffer
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_music() {
+		let mut music1 = Music::new(1);
+		let mut music2 = Music::new(1);
+		for i in 0..600 {
+			let difficulty = i as f32 / 600.;
+			let steps1 = music1.tick(1. / 60., difficulty, 0.);
+			let steps2 = music2.tick(1. / 60., difficulty, 0.);
+			assert_eq!(steps1, steps2);
+		}
+		// tempo grows with difficulty, 10 seconds above 80 bpm
+		assert!(music1.step > 10 * 80 / 60 * 4);
+		// slowdown is lower
+		let normal = Music::new(2).step_strs(1., 1.);
+		let slow = Music::new(2).step_strs(1., 0.5);
+		assert_eq!(normal.len(), slow.len());
+		assert_ne!(normal, slow);
+	}
+}
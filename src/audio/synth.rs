@@ -0,0 +1,277 @@
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::cannon::CannonType;
+
+use super::mixer::SAMPLE_RATE;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Waveform {
+	Sine,
+	Square,
+	Saw,
+	Triangle,
+	// sample and hold white noise, frequency is hold rate
+	Noise,
+}
+
+// one oscillator with envelope
+// "<wave> <freq> <freq_end> <attack> <decay> <gain> [delay]"
+// wave is sin, sqr, saw, tri or noi,
+// frequency sweeps exponentially, times are in seconds
+#[derive(Clone, Debug, PartialEq)]
+struct Layer {
+	waveform: Waveform,
+	freq: f32,
+	freq_end: f32,
+	attack: f32,
+	decay: f32,
+	gain: f32,
+	delay: f32,
+}
+
+impl Layer {
+	fn from_str(string: &str) -> Result<Layer, String> {
+		let mut splited = string.split_whitespace();
+		let waveform = match splited.next().ok_or("empty layer")? {
+			"sin" => Waveform::Sine,
+			"sqr" => Waveform::Square,
+			"saw" => Waveform::Saw,
+			"tri" => Waveform::Triangle,
+			"noi" => Waveform::Noise,
+			name => return Err(format!("unknown waveform {}", name)),
+		};
+		let args = splited
+			.map(|x| x.parse::<f32>().map_err(|e| e.to_string()))
+			.collect::<Result<Vec<f32>, String>>()?;
+		if args.len() != 5 && args.len() != 6 {
+			return Err(format!("layer takes 5 or 6 args: {}", string));
+		}
+		Ok(Layer {
+			waveform,
+			freq: args[0],
+			freq_end: args[1],
+			attack: args[2],
+			decay: args[3],
+			gain: args[4],
+			delay: args.get(5).copied().unwrap_or(0.),
+		})
+	}
+
+	fn duration(&self) -> f32 {
+		self.delay + self.attack + self.decay
+	}
+
+	// linear attack, quadratic decay
+	fn envelope(&self, t: f32) -> f32 {
+		if t < self.attack {
+			t / self.attack
+		} else {
+			let x = (1. - (t - self.attack) / self.decay).max(0.);
+			x * x
+		}
+	}
+
+	fn render(&self, buffer: &mut [f32], rng: &mut rand_pcg::Pcg64Mcg) {
+		let start = (self.delay * SAMPLE_RATE as f32) as usize;
+		let len = ((self.attack + self.decay) * SAMPLE_RATE as f32) as usize;
+		let mut phase = 0f32;
+		let mut noise = 0f32;
+		for i in 0..len.min(buffer.len().saturating_sub(start)) {
+			let t = i as f32 / SAMPLE_RATE as f32;
+			let k = t / (self.attack + self.decay);
+			let freq = self.freq * (self.freq_end / self.freq).powf(k);
+			let last_phase = phase;
+			phase = (phase + freq / SAMPLE_RATE as f32).fract();
+			let value = match self.waveform {
+				Waveform::Sine => (phase * 2. * std::f32::consts::PI).sin(),
+				Waveform::Square => {
+					if phase < 0.5 {
+						1.
+					} else {
+						-1.
+					}
+				}
+				Waveform::Saw => phase * 2. - 1.,
+				Waveform::Triangle => 1. - (phase * 4. - 2.).abs(),
+				Waveform::Noise => {
+					if phase < last_phase || i == 0 {
+						noise = rng.gen_range(-1., 1.);
+					}
+					noise
+				}
+			};
+			buffer[start + i] += value * self.envelope(t) * self.gain;
+		}
+	}
+}
+
+// layers are mixed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sound {
+	layers: Vec<Layer>,
+}
+
+impl Sound {
+	pub fn from_strs<S: AsRef<str>>(strs: &[S]) -> Result<Sound, String> {
+		Ok(Sound {
+			layers: strs
+				.iter()
+				.map(|string| Layer::from_str(string.as_ref()))
+				.collect::<Result<Vec<Layer>, String>>()?,
+		})
+	}
+
+	// noise is seeded, so a sound always renders the same
+	pub fn render(&self) -> Vec<f32> {
+		let duration = self
+			.layers
+			.iter()
+			.map(|layer| layer.duration())
+			.fold(0., f32::max);
+		let mut buffer = vec![0.; (duration * SAMPLE_RATE as f32) as usize];
+		let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(0);
+		for layer in self.layers.iter() {
+			layer.render(&mut buffer, &mut rng);
+		}
+		buffer
+	}
+}
+
+// one pole low-pass filter, in place
+pub fn low_pass(samples: &mut [f32], cutoff: f32) {
+	let alpha = 1. - (-2. * std::f32::consts::PI * cutoff / SAMPLE_RATE as f32).exp();
+	let mut last = 0.;
+	for sample in samples.iter_mut() {
+		last += (*sample - last) * alpha;
+		*sample = last;
+	}
+}
+
+fn parameter(parameters: &[(&'static str, f32)], name: &str) -> f32 {
+	parameters
+		.iter()
+		.find(|(key, _)| *key == name)
+		.map(|(_, value)| *value)
+		.unwrap_or(0.)
+}
+
+// faster bullets sound higher
+fn speed_to_freq(bullet_speed: f32) -> f32 {
+	200. + bullet_speed * 0.8
+}
+
+// sound strings of a cannon, derived from its generated parameters
+pub fn cannon_sound_strs(
+	cannon_type: CannonType,
+	parameters: &[(&'static str, f32)],
+) -> Vec<String> {
+	let p = |name| parameter(parameters, name);
+	match cannon_type {
+		// count of wedges is detuned saws, open angle widens detune
+		CannonType::PlayerLocker => {
+			let freq = 300. + p("bullet_accel") * 0.3;
+			let count = p("count").clamp(1., 5.) as usize;
+			(0..count)
+				.map(|i| {
+					let detune = 1. + (i as f32 - (count - 1) as f32 / 2.) * 0.02 * p("open_angle");
+					format!(
+						"saw {} {} 0.002 0.06 {}",
+						freq * detune,
+						freq * detune * 0.8,
+						0.3 / count as f32
+					)
+				})
+				.collect()
+		}
+		// bell of count partials
+		CannonType::Ring => {
+			let freq = speed_to_freq(p("bullet_speed"));
+			let count = p("count").clamp(1., 16.) as usize;
+			let partials = 1 + count / 4;
+			(0..partials)
+				.map(|i| {
+					let ratio = 1. + i as f32 * 1.41;
+					format!(
+						"sin {} {} 0.001 {} {}",
+						freq * ratio,
+						freq * ratio,
+						0.15 / ratio,
+						0.4 / partials as f32
+					)
+				})
+				.collect()
+		}
+		// omega is sweep depth, flipped rotors sweep down
+		CannonType::Rotor => {
+			let freq = speed_to_freq(p("bullet_speed"));
+			let sweep = 1. + p("omega") * 0.1;
+			let (start, end) = if p("flipped") > 0.5 {
+				(freq * sweep, freq)
+			} else {
+				(freq, freq * sweep)
+			};
+			vec![format!("tri {} {} 0.002 0.05 0.4", start, end)]
+		}
+		// count is chord width, open angle is interval in semitones
+		CannonType::Shotgun => {
+			let freq = speed_to_freq(p("bullet_speed")) * 0.5;
+			let count = p("count").clamp(1., 6.) as usize;
+			let interval = (p("open_angle") * 3.).clamp(1., 7.).round();
+			let mut strs: Vec<String> = (0..count)
+				.map(|i| {
+					let note = freq * 2f32.powf(i as f32 * interval / 12.);
+					format!("sqr {} {} 0.002 0.08 {}", note, note, 0.25 / count as f32)
+				})
+				.collect();
+			strs.push("noi 6000 1000 0.001 0.05 0.15".to_string());
+			strs
+		}
+		// fire duration is the length of hum
+		CannonType::LaserLocker => {
+			let freq = speed_to_freq(p("bullet_speed")) * 0.25;
+			let duration = p("fire_duration").clamp(0.1, 1.);
+			vec![
+				format!("sqr {} {} 0.01 {} 0.2", freq, freq * 0.98, duration),
+				format!("sin {} {} 0.01 {} 0.2", freq * 2., freq * 2., duration),
+			]
+		}
+		// count of slicers is noise brightness
+		CannonType::LaserSlicer => {
+			let freq = speed_to_freq(p("bullet_speed")) * 0.25;
+			let duration = p("fire_duration").clamp(0.1, 1.5);
+			let brightness = 1000. + p("count") * 500.;
+			vec![
+				format!("saw {} {} 0.02 {} 0.2", freq, freq * 0.9, duration),
+				format!(
+					"noi {} {} 0.02 {} 0.1",
+					brightness,
+					brightness * 0.5,
+					duration
+				),
+			]
+		}
+	}
+}
+
+pub fn cannon_sound(cannon_type: CannonType, parameters: &[(&'static str, f32)]) -> Sound {
+	Sound::from_strs(&cannon_sound_strs(cannon_type, parameters)).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_sound() {
+		let sound = Sound::from_strs(&["sin 440 440 0.01 0.09 0.5", "noi 8000 100 0 0.05 0.5 0.1"])
+			.unwrap();
+		let samples = sound.render();
+		assert_eq!(samples.len(), (0.15 * SAMPLE_RATE as f32) as usize);
+		assert!(samples.iter().all(|x| x.abs() <= 1.));
+		// noise layer is delayed
+		assert!(samples[(0.1 * SAMPLE_RATE as f32) as usize - 1].abs() < 0.01);
+		assert!(Sound::from_strs(&["sin 440"]).is_err());
+		assert!(Sound::from_strs(&["wav 1 1 1 1 1"]).is_err());
+	}
+}
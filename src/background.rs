@@ -66,6 +66,11 @@ impl Background {
 		self.message_queue.push_back(string);
 	}
 
+	// slowdown transition, 0 is normal and 1 is slow
+	pub fn get_shift(&self) -> f32 {
+		self.shift
+	}
+
 	pub fn tick(&mut self, dt: f32, slowing: bool) {
 		const SPEED_SCALER_V: f32 = 4.;
 		const SPEED_SCALER_H: f32 = 0.5;
@@ -0,0 +1,212 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::agent::{Action, AgentSession, Observation};
+use crate::difficulty_manager;
+use crate::game_rules::GameRules;
+use crate::record::Params;
+use crate::window_rect::WINDOW_RECT;
+
+// direction bits and unit vectors of moves considered
+const MOVES: [(u8, f32, f32); 9] = [
+	(0, 0., 0.),
+	(1, -1., 0.),
+	(2, 0., -1.),
+	(4, 1., 0.),
+	(8, 0., 1.),
+	(3, -FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+	(6, FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+	(12, FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+	(9, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+];
+// bullets closer than this(beyond radii) are dangerous
+const SAFE_CLEARANCE: f32 = 30.;
+// where the bot rests when nothing is around
+const HOME_Y: f32 = 600.;
+// cost per pixel away from target, small enough to be overridden by danger
+const ATTRACTION: f32 = 0.05;
+
+// dodges by predicting bullets one step ahead, fires continuously
+pub struct ReferenceBot {
+	dt: f32,
+	// player moves slowly when firing
+	speed: f32,
+}
+
+impl ReferenceBot {
+	pub fn new(rules: &GameRules, dt: f32) -> ReferenceBot {
+		ReferenceBot {
+			dt,
+			speed: rules.speed_slow,
+		}
+	}
+
+	// world slowdown scales player and bullets alike, so it is ignored
+	fn cost(&self, observation: &Observation, p: [f32; 2]) -> f32 {
+		let mut cost = 0.;
+		for bullet in observation.enemy_bullets.iter() {
+			let dx = bullet.p[0] + bullet.v[0] * self.dt - p[0];
+			let dy = bullet.p[1] + bullet.v[1] * self.dt - p[1];
+			let clearance = (dx * dx + dy * dy).sqrt() - bullet.r - observation.player.r;
+			if clearance < 0. {
+				cost += 1e6;
+			} else if clearance < SAFE_CLEARANCE {
+				cost += (SAFE_CLEARANCE - clearance).powi(2);
+			}
+		}
+		// aim at the lowest enemy
+		let target_x = observation
+			.enemies
+			.iter()
+			.max_by(|a, b| a.p[1].partial_cmp(&b.p[1]).unwrap())
+			.map(|enemy| enemy.p[0])
+			.unwrap_or(p[0]);
+		cost + ((target_x - p[0]).abs() + (HOME_Y - p[1]).abs()) * ATTRACTION
+	}
+
+	pub fn act(&self, observation: &Observation) -> Action {
+		let step = self.speed * self.dt;
+		let (directions, _) = MOVES
+			.iter()
+			.map(|(directions, x, y)| {
+				let p = [
+					(observation.player.p[0] + x * step).clamp(WINDOW_RECT.lu.x, WINDOW_RECT.rd.x),
+					(observation.player.p[1] + y * step).clamp(WINDOW_RECT.lu.y, WINDOW_RECT.rd.y),
+				];
+				(*directions, self.cost(observation, p))
+			})
+			.min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+			.unwrap();
+		Action {
+			directions,
+			fire: true,
+			slowdown: false,
+		}
+	}
+}
+
+pub fn subcommand() -> App<'static, 'static> {
+	SubCommand::with_name("bot")
+		.about("run the reference bot headless and report max difficulty per seed")
+		.arg(
+			Arg::with_name("seeds")
+				.short("s")
+				.long("seeds")
+				.takes_value(true)
+				.help("seeds, e.g. \"0-99\" or \"1,5,7\", default 0-9"),
+		)
+		.arg(
+			Arg::with_name("time")
+				.short("t")
+				.long("time")
+				.takes_value(true)
+				.help("game time of each run in seconds, default 300"),
+		)
+		.arg(
+			Arg::with_name("difficulty preset")
+				.short("p")
+				.long("difficulty-preset")
+				.takes_value(true)
+				.help("difficulty preset(casual, standard, marathon), default standard"),
+		)
+		.arg(
+			Arg::with_name("rules")
+				.short("r")
+				.long("rules")
+				.takes_value(true)
+				.help("game rules preset(standard, relaxed, hardcore) or rules file"),
+		)
+}
+
+fn parse_seeds(arg: &str) -> Vec<u64> {
+	arg.split(',')
+		.flat_map(|part| match part.find('-') {
+			Some(pos) => {
+				let start = part[..pos].parse::<u64>().unwrap();
+				let end = part[pos + 1..].parse::<u64>().unwrap();
+				(start..=end).collect::<Vec<u64>>()
+			}
+			None => vec![part.parse::<u64>().unwrap()],
+		})
+		.collect()
+}
+
+// max difficulty and hits
+fn run_seed(params: Params, rules: GameRules, time: f32, dt: f32) -> (f32, u32) {
+	let bot = ReferenceBot::new(&rules, dt);
+	let mut agent_session = AgentSession::new(params, rules, dt);
+	let mut observation = agent_session.observe();
+	let mut max_difficulty = observation.difficulty;
+	while observation.time < time {
+		observation = agent_session.step(bot.act(&observation));
+		max_difficulty = max_difficulty.max(observation.difficulty);
+	}
+	(max_difficulty, observation.hits)
+}
+
+pub fn run(matches: &ArgMatches) {
+	const DT: f32 = 1. / 60.;
+	let seeds = parse_seeds(matches.value_of("seeds").unwrap_or("0-9"));
+	let time = matches
+		.value_of("time")
+		.map(|time| time.parse::<f32>().unwrap())
+		.unwrap_or(300.);
+	let preset_name = matches.value_of("difficulty preset").unwrap_or("standard");
+	let (start_difficulty, curve, difficulty_drop) = difficulty_manager::preset(preset_name)
+		.unwrap_or_else(|| panic!("Unknown difficulty preset {}", preset_name));
+	let rules = match matches.value_of("rules") {
+		None => Default::default(),
+		Some(arg) => GameRules::from_arg(arg)
+			.unwrap_or_else(|e| panic!("Failed to load rules {}: {}", arg, e)),
+	};
+	println!("seed\tmax_difficulty\thits");
+	let mut sum = 0.;
+	for seed in seeds.iter() {
+		let params = Params {
+			seed: *seed,
+			start_difficulty,
+			curve: curve.clone(),
+			difficulty_drop,
+		};
+		let (max_difficulty, hits) = run_seed(params, rules.clone(), time, DT);
+		println!("{}\t{:.4}\t{}", seed, max_difficulty, hits);
+		sum += max_difficulty;
+	}
+	println!("mean\t{:.4}", sum / seeds.len() as f32);
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::agent::{BulletObservation, CircleObservation};
+
+	#[test]
+	fn test_reference_bot() {
+		assert_eq!(parse_seeds("0-2,5"), vec![0, 1, 2, 5]);
+		let bot = ReferenceBot::new(&Default::default(), 1. / 60.);
+		let mut observation = Observation {
+			time: 0.,
+			player: CircleObservation {
+				p: [250., 600.],
+				r: 5.,
+			},
+			enemies: Vec::new(),
+			enemy_bullets: Vec::new(),
+			slowdown_reserves: [1., 8.],
+			slowing: false,
+			difficulty: 0.2,
+			hits: 0,
+		};
+		assert_eq!(bot.act(&observation).directions, 0);
+		// falling on player, bot moves away
+		observation.enemy_bullets.push(BulletObservation {
+			p: [248., 590.],
+			v: [0., 300.],
+			r: 5.,
+		});
+		let action = bot.act(&observation);
+		assert!(action.fire);
+		assert_ne!(action.directions, 0);
+	}
+}
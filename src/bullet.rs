@@ -1,57 +1,61 @@
 use dyn_clone::DynClone;
 
 use crate::algebra::{Mat2x2f, Point2f};
+use crate::cannon::CannonType;
 use crate::graphic_object::GraphicObjects;
 
 pub mod bullet_graphic_objects {
 	use crate::graphic_object::GraphicObjects;
+	use crate::palette::themed_graphic_objects;
 	use lazy_static::lazy_static;
 
 	lazy_static! {
 		pub static ref RECTANGLE_PLAYER: GraphicObjects =
-			GraphicObjects::from_strs(vec!["l 1 1 0.5 0.5 -2 -10 2 -10 2 10 -2 10 -2 -10"]);
+			themed_graphic_objects(vec![
+				("player_bullet", "l 1 1 0.5 0.5 -2 -10 2 -10 2 10 -2 10 -2 -10"),
+			]);
 		pub static ref WEDGE: GraphicObjects =
-			GraphicObjects::from_strs(vec![
-				"l 1 1 0.2 0.7 -10 -5 -10 5 10 0 -10 -5",
-				"p 1 1 1 0.3 -10 -5 -10 5 10 0",
+			themed_graphic_objects(vec![
+				("enemy_bullet_a", "l 1 1 0.2 0.7 -10 -5 -10 5 10 0 -10 -5"),
+				("enemy_bullet_a", "p 1 1 1 0.3 -10 -5 -10 5 10 0"),
 			]).zoom(1.2);
 		pub static ref WEDGE2: GraphicObjects =
-			GraphicObjects::from_strs(vec![
-				"l 0.5 1 0.5 0.7 -10 -5 -10 5 10 0 -10 -5",
-				"p 0.5 1 0.8 0.3 -10 -5 -10 5 10 0",
+			themed_graphic_objects(vec![
+				("enemy_bullet_b", "l 0.5 1 0.5 0.7 -10 -5 -10 5 10 0 -10 -5"),
+				("enemy_bullet_b", "p 0.5 1 0.8 0.3 -10 -5 -10 5 10 0"),
 			]).zoom(1.2);
 		pub static ref LASER_BAR: GraphicObjects =
-			GraphicObjects::from_strs(vec!["
-				l 0.5 1 0.5 1 -10 -3 10 -3 10 3 -10 3 -10 -3"
+			themed_graphic_objects(vec![
+				("laser", "l 0.5 1 0.5 1 -10 -3 10 -3 10 3 -10 3 -10 -3"),
 			]).zoom(1.2);
 		pub static ref LASER_SOLID_BAR: GraphicObjects =
-			GraphicObjects::from_strs(vec!["
-				p 1 1 1 0.5 -15 -3 15 -3 15 3 -15 3"
+			themed_graphic_objects(vec![
+				("laser", "p 1 1 1 0.5 -15 -3 15 -3 15 3 -15 3"),
 			]).zoom(1.2);
 		pub static ref DIAMOND: GraphicObjects =
-			GraphicObjects::from_strs(vec![
-				"l 0.8 0.9 1 1 -8 0 0 6 8 0 0 -6 -8 0",
-				"p 0.8 0.6 1 0.3 -8 0 0 6 8 0 0 -6",
+			themed_graphic_objects(vec![
+				("enemy_bullet_a", "l 0.8 0.9 1 1 -8 0 0 6 8 0 0 -6 -8 0"),
+				("enemy_bullet_a", "p 0.8 0.6 1 0.3 -8 0 0 6 8 0 0 -6"),
 			]).zoom(1.6);
 		pub static ref DIAMOND2: GraphicObjects =
-			GraphicObjects::from_strs(vec![
-				"l 1 0.9 0.8 1 -8 0 0 6 8 0 0 -6 -8 0",
-				"p 1 0.8 0.6 0.3 -8 0 0 6 8 0 0 -6",
+			themed_graphic_objects(vec![
+				("enemy_bullet_b", "l 1 0.9 0.8 1 -8 0 0 6 8 0 0 -6 -8 0"),
+				("enemy_bullet_b", "p 1 0.8 0.6 0.3 -8 0 0 6 8 0 0 -6"),
 			]).zoom(1.6);
 		pub static ref FLAT_HEXAGON: GraphicObjects =
-			GraphicObjects::from_strs(vec![
-				"l 0.5 1 0.8 0.7 10 10 -10 10 -20 0 -10 -10 10 -10 20 0 10 10",
-				"p 0.1 1 0.8 0.2 10 10 -10 10 -20 0 -10 -10 10 -10 20 0",
+			themed_graphic_objects(vec![
+				("enemy_bullet", "l 0.5 1 0.8 0.7 10 10 -10 10 -20 0 -10 -10 10 -10 20 0 10 10"),
+				("enemy_bullet", "p 0.1 1 0.8 0.2 10 10 -10 10 -20 0 -10 -10 10 -10 20 0"),
 			]).zoom(0.5);
 		pub static ref SQUARE: GraphicObjects =
-			GraphicObjects::from_strs(vec![
-				"l 1 0.8 0.8 1 5 5 5 -5 -5 -5 -5 5 5 5",
-				"p 1 0.6 0.6 0.2 5 5 5 -5 -5 -5 -5 5",
+			themed_graphic_objects(vec![
+				("enemy_bullet_a", "l 1 0.8 0.8 1 5 5 5 -5 -5 -5 -5 5 5 5"),
+				("enemy_bullet_a", "p 1 0.6 0.6 0.2 5 5 5 -5 -5 -5 -5 5"),
 			]).zoom(1.6);
 		pub static ref SQUARE2: GraphicObjects =
-			GraphicObjects::from_strs(vec![
-				"l 0.8 1 1 1 5 5 5 -5 -5 -5 -5 5 5 5",
-				"p 0.6 1 1 0.2 5 5 5 -5 -5 -5 -5 5",
+			themed_graphic_objects(vec![
+				("enemy_bullet_b", "l 0.8 1 1 1 5 5 5 -5 -5 -5 -5 5 5 5"),
+				("enemy_bullet_b", "p 0.6 1 1 0.2 5 5 5 -5 -5 -5 -5 5"),
 			]).zoom(1.6);
 		//pub static ref OCTAGON: GraphicObjects =
 		//    GraphicObjects::from_strs(vec![
@@ -66,7 +70,14 @@ pub trait Bullet: DynClone {
 	fn get_shifted_graphic_objects(&self) -> GraphicObjects;
 	fn get_p(&self) -> Point2f;
 	fn get_last_p(&self) -> Point2f;
+	fn get_v(&self) -> Point2f;
 	fn get_r(&self) -> f32;
+
+	// for statistics
+	fn name(&self) -> &'static str;
+	// cannon fired this bullet, None for player bullets
+	fn get_cannon_type(&self) -> Option<CannonType>;
+	fn set_cannon_type(&mut self, cannon_type: CannonType);
 }
 
 dyn_clone::clone_trait_object!(Bullet);
@@ -79,6 +90,7 @@ pub struct SimpleBullet {
 	a: Point2f,
 	r: f32, // radius
 	graphic_objects: GraphicObjects,
+	cannon_type: Option<CannonType>,
 }
 
 impl SimpleBullet {
@@ -97,6 +109,7 @@ impl SimpleBullet {
 			a,
 			r,
 			graphic_objects,
+			cannon_type: None,
 		}
 	}
 }
@@ -110,10 +123,26 @@ impl Bullet for SimpleBullet {
 		self.last_p
 	}
 
+	fn get_v(&self) -> Point2f {
+		self.v
+	}
+
 	fn get_r(&self) -> f32 {
 		self.r
 	}
 
+	fn name(&self) -> &'static str {
+		"simple_bullet"
+	}
+
+	fn get_cannon_type(&self) -> Option<CannonType> {
+		self.cannon_type
+	}
+
+	fn set_cannon_type(&mut self, cannon_type: CannonType) {
+		self.cannon_type = Some(cannon_type);
+	}
+
 	fn get_shifted_graphic_objects(&self) -> GraphicObjects {
 		self.graphic_objects.shift(self.p)
 	}
@@ -136,6 +165,7 @@ pub struct RotateBullet {
 	theta: f32,
 	rotate_matrix: Mat2x2f,
 	graphic_objects: GraphicObjects,
+	cannon_type: Option<CannonType>,
 }
 
 impl RotateBullet {
@@ -157,6 +187,7 @@ impl RotateBullet {
 			theta: 0.,
 			rotate_matrix,
 			graphic_objects,
+			cannon_type: None,
 		}
 	}
 }
@@ -170,10 +201,26 @@ impl Bullet for RotateBullet {
 		self.last_p
 	}
 
+	fn get_v(&self) -> Point2f {
+		self.v
+	}
+
 	fn get_r(&self) -> f32 {
 		self.r
 	}
 
+	fn name(&self) -> &'static str {
+		"rotate_bullet"
+	}
+
+	fn get_cannon_type(&self) -> Option<CannonType> {
+		self.cannon_type
+	}
+
+	fn set_cannon_type(&mut self, cannon_type: CannonType) {
+		self.cannon_type = Some(cannon_type);
+	}
+
 	fn get_shifted_graphic_objects(&self) -> GraphicObjects {
 		self.graphic_objects.shift(self.p)
 	}
@@ -1,7 +1,8 @@
 use std::collections::VecDeque;
 
+use crate::agent::{point, BulletObservation};
 use crate::bullet::Bullet;
-use crate::collision::CollisionPipeInterface;
+use crate::collision::{CollisionPipeInterface, SweptCircle};
 use crate::graphic_object::{GraphicObjects, GraphicObjectsIntoIter};
 use crate::window_rect::WINDOW_RECT;
 
@@ -46,6 +47,29 @@ impl BulletPool {
 		self.bullets.push_back(bullet)
 	}
 
+	// for debug overlay
+	pub fn swept_circles(&self) -> Vec<SweptCircle> {
+		self.bullets
+			.iter()
+			.map(|bullet| SweptCircle {
+				p: bullet.get_p(),
+				last_p: bullet.get_last_p(),
+				r: bullet.get_r(),
+			})
+			.collect()
+	}
+
+	pub fn observations(&self) -> Vec<BulletObservation> {
+		self.bullets
+			.iter()
+			.map(|bullet| BulletObservation {
+				p: point(bullet.get_p()),
+				v: point(bullet.get_v()),
+				r: bullet.get_r(),
+			})
+			.collect()
+	}
+
 	pub fn graphic_objects_iter(&self) -> GraphicObjectsIntoIter {
 		let mut graphic_objects: GraphicObjects = Default::default();
 		for bullet in self.bullets.iter() {
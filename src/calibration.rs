@@ -0,0 +1,235 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::algebra::Point2f;
+use crate::bullet_pool::BulletPool;
+use crate::cannon::{self, CannonType, CANNON_TYPES};
+use crate::collision::{collision_player, CollisionPipeInterface};
+use crate::game_rules::GameRules;
+use crate::window_rect::WINDOW_RECT;
+
+const DT: f32 = 1. / 60.;
+// cannon host, same as sandbox default
+const HOST_P: (f32, f32) = (250., 200.);
+// bullets within this distance of dummy are counted as near
+const NEAR_R: f32 = 100.;
+// chance per second that dummy changes direction
+const TURN_RATE: f32 = 2.;
+
+pub fn subcommand() -> App<'static, 'static> {
+	SubCommand::with_name("calibrate")
+		.about("simulate cannons against a random-walk dummy and fit difficulty scaling")
+		.arg(
+			Arg::with_name("seeds")
+				.short("n")
+				.long("seeds")
+				.takes_value(true)
+				.help("number of seeds per difficulty, default 20"),
+		)
+		.arg(
+			Arg::with_name("difficulty")
+				.short("d")
+				.long("difficulty")
+				.takes_value(true)
+				.help("difficulties, default \"0.1,0.2,0.3,0.4,0.5,0.6,0.7,0.8,0.9,1\""),
+		)
+		.arg(
+			Arg::with_name("time")
+				.short("t")
+				.long("time")
+				.takes_value(true)
+				.help("simulated seconds of each run, default 30"),
+		)
+		.arg(
+			Arg::with_name("cannons")
+				.long("cannons")
+				.takes_value(true)
+				.help("cannon types measured, e.g. \"shotgun,rotor\", default all"),
+		)
+}
+
+// averages of one or more runs
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Measure {
+	// bullets on screen
+	bullets: f32,
+	// bullets within NEAR_R of dummy
+	near: f32,
+	// dummy hits per minute
+	hit_rate: f32,
+}
+
+// one cannon on a stationary host against a dummy walking randomly at player speed
+fn simulate(
+	cannon_type: CannonType,
+	seed: u64,
+	difficulty: f32,
+	time: f32,
+	rules: &GameRules,
+) -> Measure {
+	let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(seed);
+	// same correlation as random_mapper
+	let mut cannon = cannon::generate(cannon_type, seed, difficulty, 0.5);
+	cannon.set_rng(seed);
+	let host_p = Point2f::from_floats(HOST_P.0, HOST_P.1);
+	let mut bullet_pool = BulletPool::new();
+	let mut p = Point2f::from_floats(250., 600.);
+	let mut direction = Point2f::new();
+	let mut hit_reset_timer = 0.;
+	let mut measure: Measure = Default::default();
+	let ticks = (time / DT) as usize;
+	for _ in 0..ticks {
+		if rng.gen::<f32>() < TURN_RATE * DT {
+			direction = if rng.gen::<f32>() < 0.2 {
+				Point2f::new()
+			} else {
+				Point2f::from_theta(rng.gen_range(0., 2. * std::f32::consts::PI))
+			};
+		}
+		let last_p = p;
+		p = WINDOW_RECT.nearest(p + direction * rules.speed_slow * DT);
+		bullet_pool.tick(DT);
+		cannon.switch(true);
+		bullet_pool.extend(cannon.tick(host_p, p, DT));
+
+		measure.bullets += bullet_pool.len() as f32;
+		measure.near += bullet_pool
+			.swept_circles()
+			.iter()
+			.filter(|swept_circle| {
+				let d = swept_circle.p - p;
+				d.x * d.x + d.y * d.y < NEAR_R * NEAR_R
+			})
+			.count() as f32;
+		hit_reset_timer -= DT;
+		if hit_reset_timer <= 0.
+			&& collision_player(p, last_p, rules.player_hitbox_r, &mut bullet_pool).is_some()
+		{
+			measure.hit_rate += 1.;
+			hit_reset_timer = rules.hit_reset;
+		}
+	}
+	measure.bullets /= ticks as f32;
+	measure.near /= ticks as f32;
+	measure.hit_rate *= 60. / time;
+	measure
+}
+
+// least squares of log y = log a + b log x, points with zero y are skipped
+fn fit_power(points: &[(f32, f32)]) -> Option<(f32, f32)> {
+	let logs: Vec<(f32, f32)> = points
+		.iter()
+		.filter(|(x, y)| *x > 0. && *y > 0.)
+		.map(|(x, y)| (x.ln(), y.ln()))
+		.collect();
+	if logs.len() < 2 {
+		return None;
+	}
+	let n = logs.len() as f32;
+	let mean_x = logs.iter().map(|(x, _)| x).sum::<f32>() / n;
+	let mean_y = logs.iter().map(|(_, y)| y).sum::<f32>() / n;
+	let sxx: f32 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+	let sxy: f32 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+	if sxx == 0. {
+		return None;
+	}
+	let b = sxy / sxx;
+	Some(((mean_y - b * mean_x).exp(), b))
+}
+
+pub fn run(matches: &ArgMatches) {
+	let seeds = matches
+		.value_of("seeds")
+		.map(|seeds| seeds.parse::<u64>().unwrap())
+		.unwrap_or(20);
+	let difficulties = matches
+		.value_of("difficulty")
+		.unwrap_or("0.1,0.2,0.3,0.4,0.5,0.6,0.7,0.8,0.9,1")
+		.split(',')
+		.map(|x| x.parse::<f32>().unwrap())
+		.collect::<Vec<f32>>();
+	let time = matches
+		.value_of("time")
+		.map(|time| time.parse::<f32>().unwrap())
+		.unwrap_or(30.);
+	let cannon_types: Vec<CannonType> = match matches.value_of("cannons") {
+		None => CANNON_TYPES.to_vec(),
+		Some(cannons) => cannons
+			.split(',')
+			.map(|name| {
+				CannonType::from_name(name)
+					.unwrap_or_else(|| panic!("Unknown cannon type {}", name))
+			})
+			.collect(),
+	};
+	let rules: GameRules = Default::default();
+
+	println!("cannon\tdifficulty\tbullets\tnear\thit_rate");
+	// hit rates of each cannon type over difficulties
+	let mut curves = Vec::new();
+	for cannon_type in cannon_types.iter() {
+		let mut curve = Vec::new();
+		for difficulty in difficulties.iter() {
+			let mut mean: Measure = Default::default();
+			for seed in 0..seeds {
+				let measure = simulate(*cannon_type, seed, *difficulty, time, &rules);
+				mean.bullets += measure.bullets / seeds as f32;
+				mean.near += measure.near / seeds as f32;
+				mean.hit_rate += measure.hit_rate / seeds as f32;
+			}
+			println!(
+				"{}\t{:.2}\t{:.2}\t{:.2}\t{:.3}",
+				cannon_type.name(),
+				difficulty,
+				mean.bullets,
+				mean.near,
+				mean.hit_rate
+			);
+			curve.push((*difficulty, mean.hit_rate));
+		}
+		curves.push((*cannon_type, curve));
+	}
+
+	// hit_rate = a * difficulty ^ b,
+	// equivalent difficulty gives the reference hit rate of all cannons at 0.5
+	let fits: Vec<(CannonType, Option<(f32, f32)>)> = curves
+		.iter()
+		.map(|(cannon_type, curve)| (*cannon_type, fit_power(curve)))
+		.collect();
+	let reference_fits: Vec<(f32, f32)> = fits.iter().filter_map(|(_, fit)| *fit).collect();
+	let reference = reference_fits
+		.iter()
+		.map(|(a, b)| a * 0.5f32.powf(*b))
+		.sum::<f32>()
+		/ reference_fits.len().max(1) as f32;
+	println!();
+	println!("reference hit_rate at 0.5: {:.3}", reference);
+	println!("cannon\ta\tb\tdifficulty_at_reference");
+	for (cannon_type, fit) in fits.iter() {
+		match fit {
+			Some((a, b)) => println!(
+				"{}\t{:.3}\t{:.3}\t{:.3}",
+				cannon_type.name(),
+				a,
+				b,
+				(reference / a).powf(1. / b)
+			),
+			None => println!("{}\tno hits, not fitted", cannon_type.name()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_fit_power() {
+		let points: Vec<(f32, f32)> = [0.2, 0.5, 1.].iter().map(|x| (*x, 3. * x * x)).collect();
+		let (a, b) = fit_power(&points).unwrap();
+		assert!((a - 3.).abs() < 1e-3);
+		assert!((b - 2.).abs() < 1e-3);
+		assert_eq!(fit_power(&[(0.5, 0.), (1., 1.)]), None);
+	}
+}
@@ -5,7 +5,7 @@ use rand::SeedableRng;
 
 use crate::algebra::{Mat2x2f, Point2f};
 use crate::bullet::{bullet_graphic_objects, Bullet, SimpleBullet};
-use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface};
+use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface, CannonType};
 use crate::random_tools::simple_try;
 
 const TRY_TIMES: u32 = 10;
@@ -146,4 +146,17 @@ impl CannonControllerInterface for LaserLocker {
 	fn set_p(&mut self, p: Point2f) {
 		self.p = p;
 	}
+
+	fn cannon_type(&self) -> CannonType {
+		CannonType::LaserLocker
+	}
+
+	fn parameters(&self) -> Vec<(&'static str, f32)> {
+		vec![
+			("fire_duration", self.fire_duration),
+			("cycle_duration", self.cycle_duration),
+			("fire_interval", self.fire_interval),
+			("bullet_speed", self.bullet_speed),
+		]
+	}
 }
@@ -6,7 +6,7 @@ use rand::SeedableRng;
 
 use crate::algebra::{Mat2x2f, Point2f};
 use crate::bullet::{bullet_graphic_objects, Bullet, SimpleBullet};
-use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface};
+use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface, CannonType};
 use crate::random_tools::simple_try;
 
 const TRY_TIMES: u32 = 10;
@@ -154,6 +154,20 @@ impl CannonControllerInterface for LaserSlicer {
 		self.p = p;
 	}
 
+	fn cannon_type(&self) -> CannonType {
+		CannonType::LaserSlicer
+	}
+
+	fn parameters(&self) -> Vec<(&'static str, f32)> {
+		vec![
+			("fire_duration", self.fire_duration),
+			("cycle_duration", self.cycle_duration),
+			("fire_interval", self.fire_interval),
+			("count", self.count as f32),
+			("bullet_speed", self.bullet_speed),
+		]
+	}
+
 	fn set_rng(&mut self, seed: u64) {
 		self.rng = Some(rand_pcg::Pcg64Mcg::seed_from_u64(seed));
 	}
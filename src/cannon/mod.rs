@@ -19,6 +19,7 @@ pub use simple_cannon::SimpleCannon;
 use dyn_clone::DynClone;
 use rand::Rng;
 use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
 
 use crate::algebra::Point2f;
 use crate::bullet::Bullet;
@@ -32,6 +33,10 @@ pub trait CannonControllerInterface: DynClone + Send {
 
 	// for enemy prototype
 	fn set_rng(&mut self, _: u64) {}
+
+	// generated parameters, for display and analysis
+	fn parameters(&self) -> Vec<(&'static str, f32)>;
+	fn cannon_type(&self) -> CannonType;
 }
 
 dyn_clone::clone_trait_object!(CannonControllerInterface);
@@ -40,47 +45,90 @@ trait CannonGeneratorInterface {
 	fn generate(seed: u64, difficulty: f32, correlation: f32) -> Self;
 }
 
-pub fn random_mapper(seed: u64, difficulty: f32, pool: u32) -> Box<dyn CannonControllerInterface> {
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum CannonType {
+	PlayerLocker,
+	Ring,
+	Rotor,
+	Shotgun,
+	LaserLocker,
+	LaserSlicer,
+}
+
+// index is the id used by random_mapper, never reorder
+pub const CANNON_TYPES: [CannonType; 6] = [
+	CannonType::PlayerLocker,
+	CannonType::Ring,
+	CannonType::Rotor,
+	CannonType::Shotgun,
+	CannonType::LaserLocker,
+	CannonType::LaserSlicer,
+];
+
+impl CannonType {
+	pub fn name(self) -> &'static str {
+		match self {
+			CannonType::PlayerLocker => "player_locker",
+			CannonType::Ring => "ring",
+			CannonType::Rotor => "rotor",
+			CannonType::Shotgun => "shotgun",
+			CannonType::LaserLocker => "laser_locker",
+			CannonType::LaserSlicer => "laser_slicer",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<CannonType> {
+		CANNON_TYPES
+			.iter()
+			.copied()
+			.find(|cannon_type| cannon_type.name() == name)
+	}
+}
+
+pub fn generate(
+	cannon_type: CannonType,
+	seed: u64,
+	difficulty: f32,
+	correlation: f32,
+) -> Box<dyn CannonControllerInterface> {
+	match cannon_type {
+		CannonType::PlayerLocker => Box::new(PlayerLocker::generate(seed, difficulty, correlation)),
+		CannonType::Ring => Box::new(Ring::generate(seed, difficulty, correlation)),
+		CannonType::Rotor => Box::new(Rotor::generate(seed, difficulty, correlation)),
+		CannonType::Shotgun => Box::new(Shotgun::generate(seed, difficulty, correlation)),
+		CannonType::LaserLocker => Box::new(LaserLocker::generate(seed, difficulty, correlation)),
+		CannonType::LaserSlicer => Box::new(LaserSlicer::generate(seed, difficulty, correlation)),
+	}
+}
+
+// allowed restricts types in pool(for practice),
+// if none of allowed types is in pool, allowed types are used
+pub fn random_mapper(
+	seed: u64,
+	difficulty: f32,
+	pool: u32,
+	allowed: Option<&[CannonType]>,
+) -> Box<dyn CannonControllerInterface> {
 	let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(seed);
 	let correlation: f32 = 0.5;
-	match pool {
-		0 => match rng.gen_range(0, 5) {
-			0 => Box::new(PlayerLocker::generate(
-				rng.gen::<u64>(),
-				difficulty,
-				correlation,
-			)),
-			1 => Box::new(Ring::generate(rng.gen::<u64>(), difficulty, correlation)),
-			2 => Box::new(Rotor::generate(rng.gen::<u64>(), difficulty, correlation)),
-			3 => Box::new(Shotgun::generate(rng.gen::<u64>(), difficulty, correlation)),
-			4 => Box::new(LaserLocker::generate(
-				rng.gen::<u64>(),
-				difficulty,
-				correlation,
-			)),
-			_ => unreachable!(),
-		},
-		1 => match rng.gen_range(0, 6) {
-			0 => Box::new(PlayerLocker::generate(
-				rng.gen::<u64>(),
-				difficulty,
-				correlation,
-			)),
-			1 => Box::new(Ring::generate(rng.gen::<u64>(), difficulty, correlation)),
-			2 => Box::new(Rotor::generate(rng.gen::<u64>(), difficulty, correlation)),
-			3 => Box::new(Shotgun::generate(rng.gen::<u64>(), difficulty, correlation)),
-			4 => Box::new(LaserLocker::generate(
-				rng.gen::<u64>(),
-				difficulty,
-				correlation,
-			)),
-			5 => Box::new(LaserSlicer::generate(
-				rng.gen::<u64>(),
-				difficulty,
-				correlation,
-			)),
-			_ => unreachable!(),
-		},
-		_ => unreachable! {},
-	}
+	let pool_size = match pool {
+		0 => 5,
+		1 => 6,
+		_ => unreachable!(),
+	};
+	let cannon_type = match allowed {
+		None => CANNON_TYPES[rng.gen_range(0, pool_size)],
+		Some(allowed) => {
+			let mut candidates: Vec<CannonType> = CANNON_TYPES[..pool_size]
+				.iter()
+				.copied()
+				.filter(|cannon_type| allowed.contains(cannon_type))
+				.collect();
+			if candidates.is_empty() {
+				candidates = allowed.to_vec();
+			}
+			candidates[rng.gen_range(0, candidates.len())]
+		}
+	};
+	generate(cannon_type, rng.gen::<u64>(), difficulty, correlation)
 }
@@ -5,7 +5,7 @@ use rand::SeedableRng;
 
 use crate::algebra::{Mat2x2f, Point2f};
 use crate::bullet::{bullet_graphic_objects, Bullet, SimpleBullet};
-use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface};
+use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface, CannonType};
 use crate::random_tools::simple_try;
 
 use mray::graphic_object::GraphicObjects;
@@ -136,4 +136,17 @@ impl CannonControllerInterface for PlayerLocker {
 	fn set_p(&mut self, p: Point2f) {
 		self.p = p;
 	}
+
+	fn cannon_type(&self) -> CannonType {
+		CannonType::PlayerLocker
+	}
+
+	fn parameters(&self) -> Vec<(&'static str, f32)> {
+		vec![
+			("fire_interval", self.fire_interval),
+			("open_angle", self.open_angle),
+			("count", self.count as f32),
+			("bullet_accel", self.bullet_accel),
+		]
+	}
 }
@@ -5,7 +5,7 @@ use rand::SeedableRng;
 
 use crate::algebra::{Mat2x2f, Point2f};
 use crate::bullet::{bullet_graphic_objects, Bullet, SimpleBullet};
-use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface};
+use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface, CannonType};
 use crate::random_tools::simple_try;
 
 const TRY_TIMES: u32 = 10;
@@ -99,6 +99,18 @@ impl CannonControllerInterface for Ring {
 		self.p = p;
 	}
 
+	fn cannon_type(&self) -> CannonType {
+		CannonType::Ring
+	}
+
+	fn parameters(&self) -> Vec<(&'static str, f32)> {
+		vec![
+			("fire_interval", self.fire_interval),
+			("count", self.count as f32),
+			("bullet_speed", self.bullet_speed),
+		]
+	}
+
 	fn set_rng(&mut self, seed: u64) {
 		self.rng = Some(rand_pcg::Pcg64Mcg::seed_from_u64(seed));
 	}
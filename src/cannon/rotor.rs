@@ -5,7 +5,7 @@ use rand::SeedableRng;
 
 use crate::algebra::{Mat2x2f, Point2f};
 use crate::bullet::{bullet_graphic_objects, Bullet, RotateBullet};
-use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface};
+use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface, CannonType};
 use crate::random_tools::simple_try;
 
 use mray::graphic_object::GraphicObjects;
@@ -112,4 +112,17 @@ impl CannonControllerInterface for Rotor {
 	fn set_p(&mut self, p: Point2f) {
 		self.p = p;
 	}
+
+	fn cannon_type(&self) -> CannonType {
+		CannonType::Rotor
+	}
+
+	fn parameters(&self) -> Vec<(&'static str, f32)> {
+		vec![
+			("fire_interval", self.fire_interval),
+			("omega", self.omega),
+			("flipped", self.flipped as i32 as f32),
+			("bullet_speed", self.bullet_speed),
+		]
+	}
 }
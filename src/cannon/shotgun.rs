@@ -5,7 +5,7 @@ use rand::SeedableRng;
 
 use crate::algebra::{Mat2x2f, Point2f};
 use crate::bullet::{bullet_graphic_objects, Bullet, RotateBullet};
-use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface};
+use crate::cannon::{CannonControllerInterface, CannonGeneratorInterface, CannonType};
 use crate::random_tools::simple_try;
 
 use mray::graphic_object::GraphicObjects;
@@ -156,6 +156,20 @@ impl CannonControllerInterface for Shotgun {
 		self.p = p;
 	}
 
+	fn cannon_type(&self) -> CannonType {
+		CannonType::Shotgun
+	}
+
+	fn parameters(&self) -> Vec<(&'static str, f32)> {
+		vec![
+			("fire_interval", self.fire_interval),
+			("open_angle", self.open_angle),
+			("count", self.count as f32),
+			("bullet_speed", self.bullet_speed),
+			("subtype", self.subtype as f32),
+		]
+	}
+
 	fn set_rng(&mut self, seed: u64) {
 		self.rng = Some(rand_pcg::Pcg64Mcg::seed_from_u64(seed));
 	}
@@ -0,0 +1,201 @@
+use crate::algebra::{Point2f, Rect2f};
+use crate::graphic_object::{GraphicObject, LineSegs2f, Polygon2f};
+
+// Liang-Barsky, return parameters of clipped segment on p1 + (p2 - p1) * t
+fn clip_segment(p1: Point2f, p2: Point2f, rect: &Rect2f) -> Option<(f32, f32)> {
+	let d = p2 - p1;
+	let mut t0 = 0f32;
+	let mut t1 = 1f32;
+	for (p, q) in [
+		(-d.x, p1.x - rect.lu.x),
+		(d.x, rect.rd.x - p1.x),
+		(-d.y, p1.y - rect.lu.y),
+		(d.y, rect.rd.y - p1.y),
+	]
+	.iter()
+	{
+		if *p == 0. {
+			// parallel to this edge
+			if *q < 0. {
+				return None;
+			}
+			continue;
+		}
+		let t = q / p;
+		if *p < 0. {
+			t0 = t0.max(t);
+		} else {
+			t1 = t1.min(t);
+		}
+		if t0 > t1 {
+			return None;
+		}
+	}
+	Some((t0, t1))
+}
+
+// polyline is split into pieces where it leaves rect
+fn clip_polyline(vertices: &[Point2f], rect: &Rect2f) -> Vec<Vec<Point2f>> {
+	let mut pieces = Vec::new();
+	let mut piece: Vec<Point2f> = Vec::new();
+	for segment in vertices.windows(2) {
+		let (p1, p2) = (segment[0], segment[1]);
+		if let Some((t0, t1)) = clip_segment(p1, p2, rect) {
+			if piece.is_empty() {
+				piece.push(p1 + (p2 - p1) * t0);
+			}
+			piece.push(p1 + (p2 - p1) * t1);
+			if t1 >= 1. {
+				continue;
+			}
+		}
+		if piece.len() >= 2 {
+			pieces.push(std::mem::take(&mut piece));
+		}
+		piece.clear();
+	}
+	if piece.len() >= 2 {
+		pieces.push(piece);
+	}
+	pieces
+}
+
+// Sutherland-Hodgman, polygon is implicitly closed
+fn clip_polygon(vertices: &[Point2f], rect: &Rect2f) -> Vec<Point2f> {
+	let mut output = vertices.to_vec();
+	// (is x axis, bound, inside is greater)
+	for (x_axis, bound, greater) in [
+		(true, rect.lu.x, true),
+		(true, rect.rd.x, false),
+		(false, rect.lu.y, true),
+		(false, rect.rd.y, false),
+	]
+	.iter()
+	{
+		let input = std::mem::take(&mut output);
+		let value = |p: Point2f| if *x_axis { p.x } else { p.y };
+		let inside = |p: Point2f| {
+			if *greater {
+				value(p) >= *bound
+			} else {
+				value(p) <= *bound
+			}
+		};
+		let intersect =
+			|a: Point2f, b: Point2f| a + (b - a) * ((bound - value(a)) / (value(b) - value(a)));
+		let mut last = match input.last() {
+			None => break,
+			Some(last) => *last,
+		};
+		for p in input.into_iter() {
+			if inside(p) {
+				if !inside(last) {
+					output.push(intersect(last, p));
+				}
+				output.push(p);
+			} else if inside(last) {
+				output.push(intersect(last, p));
+			}
+			last = p;
+		}
+	}
+	output
+}
+
+fn bounding_box(vertices: &[Point2f]) -> Rect2f {
+	let mut bounding_box = Rect2f::from_floats(
+		f32::INFINITY,
+		f32::INFINITY,
+		f32::NEG_INFINITY,
+		f32::NEG_INFINITY,
+	);
+	for vertex in vertices.iter() {
+		bounding_box.lu.x = bounding_box.lu.x.min(vertex.x);
+		bounding_box.lu.y = bounding_box.lu.y.min(vertex.y);
+		bounding_box.rd.x = bounding_box.rd.x.max(vertex.x);
+		bounding_box.rd.y = bounding_box.rd.y.max(vertex.y);
+	}
+	bounding_box
+}
+
+// LineSegs2f and Polygon2f are clipped to rect, other objects are kept as is
+pub fn clip(graphic_object: Box<dyn GraphicObject>, rect: &Rect2f) -> Vec<Box<dyn GraphicObject>> {
+	let vertices = if let Some(line_segs) = graphic_object.as_any().downcast_ref::<LineSegs2f>() {
+		&line_segs.vertices
+	} else if let Some(polygon) = graphic_object.as_any().downcast_ref::<Polygon2f>() {
+		&polygon.vertices
+	} else {
+		return vec![graphic_object];
+	};
+	let bounding_box = bounding_box(vertices);
+	if bounding_box.rd.x < rect.lu.x
+		|| bounding_box.lu.x > rect.rd.x
+		|| bounding_box.rd.y < rect.lu.y
+		|| bounding_box.lu.y > rect.rd.y
+	{
+		return Vec::new();
+	}
+	if rect.contain(bounding_box.lu) && rect.contain(bounding_box.rd) {
+		return vec![graphic_object];
+	}
+
+	let mut result: Vec<Box<dyn GraphicObject>> = Vec::new();
+	if let Some(line_segs) = graphic_object.as_any().downcast_ref::<LineSegs2f>() {
+		for vertices in clip_polyline(&line_segs.vertices, rect).into_iter() {
+			result.push(Box::new(LineSegs2f {
+				vertices,
+				color: line_segs.color,
+			}));
+		}
+	} else if let Some(polygon) = graphic_object.as_any().downcast_ref::<Polygon2f>() {
+		let vertices = clip_polygon(&polygon.vertices, rect);
+		if vertices.len() >= 3 {
+			result.push(Box::new(Polygon2f {
+				vertices,
+				color: polygon.color,
+				border_color: polygon.border_color,
+			}));
+		}
+	}
+	result
+}
+
+#[cfg(test)]
+mod test {
+	use super::{clip_polygon, clip_polyline};
+	use crate::algebra::{Point2f, Rect2f};
+
+	#[test]
+	fn test_clip() {
+		let rect = Rect2f::from_floats(0., 0., 10., 10.);
+		// enters, leaves and enters again
+		let pieces = clip_polyline(
+			&[
+				Point2f::from_floats(-5., 5.),
+				Point2f::from_floats(5., 5.),
+				Point2f::from_floats(15., 5.),
+				Point2f::from_floats(5., 8.),
+			],
+			&rect,
+		);
+		assert_eq!(pieces.len(), 2);
+		assert_eq!(pieces[0].len(), 3);
+		assert!((pieces[0][0].x - 0.).abs() < 1e-5 && (pieces[0][2].x - 10.).abs() < 1e-5);
+		assert_eq!(pieces[1].len(), 2);
+
+		// square overlapping the corner becomes a smaller square
+		let vertices = clip_polygon(
+			&[
+				Point2f::from_floats(-5., -5.),
+				Point2f::from_floats(5., -5.),
+				Point2f::from_floats(5., 5.),
+				Point2f::from_floats(-5., 5.),
+			],
+			&rect,
+		);
+		assert_eq!(vertices.len(), 4);
+		for vertex in vertices.iter() {
+			assert!(vertex.x >= 0. && vertex.x <= 5. && vertex.y >= 0. && vertex.y <= 5.);
+		}
+	}
+}
@@ -1,8 +1,18 @@
 use crate::algebra::{linesegs_distance, Point2f};
+use crate::bullet::Bullet;
 use crate::bullet_pool::BulletPool;
 use crate::destroy_effect::DestroyedObjects;
 use crate::enemy_pool::EnemyPool;
 
+// a circle moved from last_p to p in one tick
+// collision is tested by distance of the swept segments
+#[derive(Clone, Copy, Debug)]
+pub struct SweptCircle {
+	pub p: Point2f,
+	pub last_p: Point2f,
+	pub r: f32,
+}
+
 pub trait CollisionPipeInterface {
 	type Object;
 
@@ -11,15 +21,17 @@ pub trait CollisionPipeInterface {
 	fn len(&self) -> usize;
 }
 
+// return number of destroyed enemies
 pub fn collision_enemy(
 	enemy_pool: &mut EnemyPool,
 	player_bullet_pool: &mut BulletPool,
 	destroyed_objects: &mut DestroyedObjects,
-) {
+) -> u32 {
 	// Time complexity notes:
 	// O(l_e * l_pb)
 	// player_bullet_pool < 10^2
 	// enemy_pool < 10^2
+	let mut destroyed_count = 0;
 	let enemy_len = enemy_pool.len();
 	for _ in 0..enemy_len {
 		let mut enemy = enemy_pool.pop().unwrap();
@@ -68,16 +80,18 @@ pub fn collision_enemy(
 			enemy_pool.push(enemy);
 		} else {
 			destroyed_objects.push(enemy.get_shifted_graphic_objects());
+			destroyed_count += 1;
 		}
 	}
+	destroyed_count
 }
 
 pub fn collision_player(
 	player_p: Point2f,
 	player_last_p: Point2f,
+	player_hitbox_r: f32,
 	enemy_bullet_pool: &mut BulletPool,
-) -> bool {
-	const PLAYER_HITBOX_R: f32 = 5.;
+) -> Option<Box<dyn Bullet>> {
 	let bullet_len = enemy_bullet_pool.len();
 	for _ in 0..bullet_len {
 		let bullet = enemy_bullet_pool.pop().unwrap();
@@ -85,11 +99,11 @@ pub fn collision_player(
 		let bullet_last_p = bullet.get_last_p();
 		let dist = linesegs_distance(player_p, player_last_p, bullet_p, bullet_last_p);
 		//println!("{} {:?} {:?}", dist, bullet_p, bullet_last_p);
-		if dist < PLAYER_HITBOX_R + bullet.get_r() {
+		if dist < player_hitbox_r + bullet.get_r() {
 			// remove first hit bullet, and keep remains
-			return true;
+			return Some(bullet);
 		}
 		enemy_bullet_pool.push(bullet);
 	}
-	false
+	None
 }
@@ -0,0 +1,103 @@
+use crate::algebra::Point2f;
+use crate::collision::SweptCircle;
+use crate::graphic_object::{GraphicObjects, LineSegs2f};
+
+// seconds of last hit highlight, unscaled
+const HIT_HIGHLIGHT_TIME: f32 = 1.;
+const CIRCLE_SEGMENTS: usize = 16;
+
+const ENEMY_COLOR: [f32; 4] = [0.3, 1., 0.3, 0.8];
+const ENEMY_BULLET_COLOR: [f32; 4] = [1., 0.5, 0.3, 0.8];
+const PLAYER_BULLET_COLOR: [f32; 4] = [0.3, 0.8, 1., 0.5];
+const PLAYER_COLOR: [f32; 4] = [1., 1., 0.3, 1.];
+
+fn swept_circle_graphic_objects(swept_circle: &SweptCircle, color: [f32; 4]) -> GraphicObjects {
+	let mut graphic_objects: GraphicObjects = Default::default();
+	let vertices = (0..=CIRCLE_SEGMENTS)
+		.map(|i| {
+			let theta = i as f32 / CIRCLE_SEGMENTS as f32 * 2. * std::f32::consts::PI;
+			swept_circle.p + Point2f::from_floats(theta.cos(), theta.sin()) * swept_circle.r
+		})
+		.collect();
+	graphic_objects.push(Box::new(LineSegs2f { vertices, color }));
+	graphic_objects.push(Box::new(LineSegs2f {
+		vertices: vec![swept_circle.last_p, swept_circle.p],
+		color,
+	}));
+	graphic_objects
+}
+
+// shows what collision sees: hitboxes, bullet radii and swept segments
+pub struct DebugOverlay {
+	switch: bool,
+	// player and bullet of last hit, and remaining highlight time
+	last_hit: Option<(SweptCircle, SweptCircle, f32)>,
+}
+
+impl DebugOverlay {
+	pub fn new() -> DebugOverlay {
+		DebugOverlay {
+			switch: false,
+			last_hit: None,
+		}
+	}
+
+	pub fn switch(&mut self) {
+		self.switch = !self.switch;
+	}
+
+	pub fn is_on(&self) -> bool {
+		self.switch
+	}
+
+	// recorded even if overlay is off, so it shows once switched on
+	pub fn hit(&mut self, player: SweptCircle, bullet: SweptCircle) {
+		self.last_hit = Some((player, bullet, HIT_HIGHLIGHT_TIME));
+	}
+
+	pub fn tick(&mut self, dt: f32) {
+		if let Some((_, _, timer)) = self.last_hit.as_mut() {
+			*timer -= dt;
+			if *timer <= 0. {
+				self.last_hit = None;
+			}
+		}
+	}
+
+	pub fn graphic_objects(
+		&self,
+		player: SweptCircle,
+		enemies: Vec<SweptCircle>,
+		enemy_bullets: Vec<SweptCircle>,
+		player_bullets: Vec<SweptCircle>,
+	) -> GraphicObjects {
+		let mut graphic_objects: GraphicObjects = Default::default();
+		for (swept_circles, color) in [
+			(enemies, ENEMY_COLOR),
+			(enemy_bullets, ENEMY_BULLET_COLOR),
+			(player_bullets, PLAYER_BULLET_COLOR),
+		]
+		.iter()
+		{
+			for swept_circle in swept_circles.iter() {
+				graphic_objects.extend(swept_circle_graphic_objects(swept_circle, *color));
+			}
+		}
+		graphic_objects.extend(swept_circle_graphic_objects(&player, PLAYER_COLOR));
+		if let Some((player, bullet, timer)) = self.last_hit.as_ref() {
+			// frozen at hit frame, fading out
+			let color = [1., 0.2, 0.2, timer / HIT_HIGHLIGHT_TIME];
+			graphic_objects.extend(swept_circle_graphic_objects(player, color));
+			graphic_objects.extend(swept_circle_graphic_objects(bullet, color));
+			// the hit bullet is enlarged to be noticed
+			graphic_objects.extend(swept_circle_graphic_objects(
+				&SweptCircle {
+					r: bullet.r + 10.,
+					..*bullet
+				},
+				color,
+			));
+		}
+		graphic_objects
+	}
+}
@@ -1,24 +1,151 @@
+use serde::{Deserialize, Serialize};
+
 // define "one level"
 pub const DIFFICULTY_MULTIPLIER: f32 = 100.;
+// time constant of hit rate average, in seconds
+const HIT_RATE_WINDOW: f32 = 60.;
+
+// how difficulty grows with (scaled) game time
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum DifficultyCurve {
+	// growth per second
+	Linear { growth: f32 },
+	// growth / (1 + t / time_scale) per second, integrated as a log curve
+	Logarithmic { growth: f32, time_scale: f32 },
+	// plateaus of duration seconds, each raises step
+	Stepped { step: f32, duration: f32 },
+	// growth is scaled by how far hit rate(per minute) is below target
+	// and becomes negative if player is hit too often
+	RubberBand { growth: f32, target_hit_rate: f32 },
+}
+
+pub const CURVE_NAMES: [&str; 4] = ["linear", "log", "stepped", "rubber"];
+
+impl DifficultyCurve {
+	// e.g. "linear 0.0005", "log 0.002 60", "stepped 0.01 20", "rubber 0.001 3"
+	pub fn from_str(line: &str) -> Result<DifficultyCurve, String> {
+		let mut splited = line.split_whitespace();
+		let name = splited.next().ok_or("empty curve")?;
+		let args = splited
+			.map(|x| x.parse::<f32>().map_err(|e| e.to_string()))
+			.collect::<Result<Vec<f32>, String>>()?;
+		let expected_len = match name {
+			"linear" => 1,
+			"log" | "stepped" | "rubber" => 2,
+			_ => return Err(format!("unknown curve {}", name)),
+		};
+		if args.len() != expected_len {
+			return Err(format!("curve {} takes {} args", name, expected_len));
+		}
+		Ok(match name {
+			"linear" => DifficultyCurve::Linear { growth: args[0] },
+			"log" => DifficultyCurve::Logarithmic {
+				growth: args[0],
+				time_scale: args[1],
+			},
+			"stepped" => DifficultyCurve::Stepped {
+				step: args[0],
+				duration: args[1],
+			},
+			"rubber" => DifficultyCurve::RubberBand {
+				growth: args[0],
+				target_hit_rate: args[1],
+			},
+			_ => unreachable!(),
+		})
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			DifficultyCurve::Linear { .. } => "linear",
+			DifficultyCurve::Logarithmic { .. } => "log",
+			DifficultyCurve::Stepped { .. } => "stepped",
+			DifficultyCurve::RubberBand { .. } => "rubber",
+		}
+	}
+
+	// curve of name with default args
+	pub fn from_name(name: &str) -> Option<DifficultyCurve> {
+		match name {
+			"linear" => Some(DifficultyCurve::Linear { growth: 0.0005 }),
+			"log" => Some(DifficultyCurve::Logarithmic {
+				growth: 0.002,
+				time_scale: 60.,
+			}),
+			"stepped" => Some(DifficultyCurve::Stepped {
+				step: 0.01,
+				duration: 20.,
+			}),
+			"rubber" => Some(DifficultyCurve::RubberBand {
+				growth: 0.001,
+				target_hit_rate: 3.,
+			}),
+			_ => None,
+		}
+	}
+
+	// the main growth parameter, edited in menu
+	pub fn growth_mut(&mut self) -> &mut f32 {
+		match self {
+			DifficultyCurve::Linear { growth } => growth,
+			DifficultyCurve::Logarithmic { growth, .. } => growth,
+			DifficultyCurve::Stepped { step, .. } => step,
+			DifficultyCurve::RubberBand { growth, .. } => growth,
+		}
+	}
+}
+
+pub const PRESETS: [&str; 3] = ["casual", "standard", "marathon"];
+
+// start difficulty, curve, drop
+pub fn preset(name: &str) -> Option<(f32, DifficultyCurve, f32)> {
+	match name {
+		"casual" => Some((
+			0.1,
+			DifficultyCurve::Logarithmic {
+				growth: 0.002,
+				time_scale: 60.,
+			},
+			0.001,
+		)),
+		"standard" => Some((0.2, DifficultyCurve::Linear { growth: 0.0005 }, 0.002)),
+		"marathon" => Some((
+			0.15,
+			DifficultyCurve::Stepped {
+				step: 0.01,
+				duration: 30.,
+			},
+			0.003,
+		)),
+		_ => None,
+	}
+}
 
 pub struct DifficultyManager {
 	difficulty: f32,
 	last_difficulty: u32,
 	min_difficulty: f32,
 	max_difficulty: Option<f32>,
-	difficulty_growth: f32,
+	curve: DifficultyCurve,
 	difficulty_drop: f32,
+
+	// curve states
+	timer: f32,
+	// exponential moving average of hits per second
+	hit_rate: f32,
 }
 
 impl DifficultyManager {
-	pub fn new(difficulty: f32, difficulty_growth: f32, difficulty_drop: f32) -> DifficultyManager {
+	pub fn new(difficulty: f32, curve: DifficultyCurve, difficulty_drop: f32) -> DifficultyManager {
 		DifficultyManager {
 			difficulty,
 			last_difficulty: (difficulty * DIFFICULTY_MULTIPLIER) as u32,
 			min_difficulty: difficulty,
 			max_difficulty: None,
-			difficulty_growth,
+			curve,
 			difficulty_drop,
+			timer: 0.,
+			hit_rate: 0.,
 		}
 	}
 
@@ -31,16 +158,43 @@ impl DifficultyManager {
 	}
 
 	pub fn drop(&mut self) {
+		self.hit_rate += 1. / HIT_RATE_WINDOW;
 		self.difficulty -= self.difficulty_drop;
 		if self.difficulty < 0. {
 			self.difficulty = 0.;
 		}
 	}
 
+	fn growth(&mut self, dt: f32) -> f32 {
+		self.timer += dt;
+		self.hit_rate -= self.hit_rate * dt / HIT_RATE_WINDOW;
+		match self.curve {
+			DifficultyCurve::Linear { growth } => growth * dt,
+			DifficultyCurve::Logarithmic { growth, time_scale } => {
+				growth * dt / (1. + self.timer / time_scale)
+			}
+			DifficultyCurve::Stepped { step, duration } => {
+				if self.timer >= duration {
+					self.timer -= duration;
+					step
+				} else {
+					0.
+				}
+			}
+			DifficultyCurve::RubberBand {
+				growth,
+				target_hit_rate,
+			} => {
+				let ratio = self.hit_rate * 60. / target_hit_rate;
+				growth * (1. - ratio).max(-1.) * dt
+			}
+		}
+	}
+
 	// return +- if difficulty * DIFFICULTY_MULTIPLIER just passed an integer
 	// return true if high score is updated
 	pub fn tick(&mut self, dt: f32) -> (std::cmp::Ordering, bool) {
-		self.difficulty += self.difficulty_growth * dt;
+		self.difficulty = (self.difficulty + self.growth(dt)).max(0.);
 		if self.difficulty < self.min_difficulty {
 			self.min_difficulty = self.difficulty
 		}
@@ -0,0 +1,105 @@
+use crate::algebra::Rect2f;
+use crate::canvas::Canvas;
+use crate::clip::clip;
+use crate::graphic_object::GraphicObject;
+use crate::retro;
+use crate::window_rect::{frame_size, scaled_size, PANEL_RECT, WINDOW_RECT};
+
+// all scenes are drawn through display,
+// which clips objects to the playfield and applies render style
+pub struct Display {
+	canvas: Canvas,
+	scaler: f32,
+	size: (u32, u32),
+	retro: bool,
+	// frame is widened by side panel
+	panel: bool,
+	// in retro mode canvas keeps phosphor, output has bloom added
+	retro_output: Vec<u8>,
+}
+
+impl Display {
+	pub fn new(scaler: f32, retro: bool, panel: bool) -> Display {
+		let frame_size = frame_size(panel);
+		Display {
+			canvas: Canvas::new((frame_size.x as i32, frame_size.y as i32), scaler),
+			scaler,
+			size: scaled_size(scaler, frame_size),
+			retro,
+			panel,
+			retro_output: Vec::new(),
+		}
+	}
+
+	pub fn set_scaler(&mut self, scaler: f32) {
+		*self = Display::new(scaler, self.retro, self.panel);
+	}
+
+	pub fn has_panel(&self) -> bool {
+		self.panel
+	}
+
+	pub fn set_panel(&mut self, panel: bool) {
+		*self = Display::new(self.scaler, self.retro, panel);
+	}
+
+	pub fn get_size(&self) -> (u32, u32) {
+		self.size
+	}
+
+	pub fn set_retro(&mut self, retro: bool) {
+		self.retro = retro;
+	}
+
+	// dt is used for phosphor decay
+	pub fn begin_frame(&mut self, dt: f32) {
+		if self.retro {
+			retro::decay(&mut self.canvas.data, dt);
+		} else {
+			self.canvas.flush();
+		}
+	}
+
+	// draw on playfield
+	pub fn draw<I>(&mut self, graphic_objects: I)
+	where
+		I: IntoIterator<Item = Box<dyn GraphicObject>>,
+	{
+		self.draw_in(graphic_objects, &WINDOW_RECT);
+	}
+
+	// draw on side panel, ignored if panel is off
+	pub fn draw_panel<I>(&mut self, graphic_objects: I)
+	where
+		I: IntoIterator<Item = Box<dyn GraphicObject>>,
+	{
+		if self.panel {
+			self.draw_in(graphic_objects, &PANEL_RECT);
+		}
+	}
+
+	fn draw_in<I>(&mut self, graphic_objects: I, rect: &Rect2f)
+	where
+		I: IntoIterator<Item = Box<dyn GraphicObject>>,
+	{
+		for graphic_object in graphic_objects.into_iter() {
+			for clipped in clip(graphic_object, rect) {
+				if self.retro {
+					retro::outline(clipped).render(&mut self.canvas);
+				} else {
+					clipped.render(&mut self.canvas);
+				}
+			}
+		}
+	}
+
+	// RGB24 frame of get_size
+	pub fn get_data(&mut self) -> &[u8] {
+		if self.retro {
+			retro::bloom(&self.canvas.data, self.size, &mut self.retro_output);
+			&self.retro_output
+		} else {
+			&self.canvas.data
+		}
+	}
+}
@@ -1,6 +1,8 @@
 use std::collections::VecDeque;
+use std::sync::Arc;
 
 use crate::algebra::{Circle2f, Point2f};
+use crate::audio::CannonSound;
 use crate::bullet::Bullet;
 use crate::cannon::CannonControllerInterface;
 use crate::enemy_path::EnemyPath;
@@ -8,52 +10,98 @@ use crate::graphic_object::GraphicObjects;
 
 mod enemy_graphic_objects {
 	use crate::graphic_object::GraphicObjects;
+	use crate::palette::themed_graphic_objects;
 	use lazy_static::lazy_static;
 
 	lazy_static! {
-		pub static ref SMALL1: GraphicObjects = GraphicObjects::from_strs(vec![
-			"l 0.3 0.5 0.5 1 -0.5 0.5 -0.5 -0.5 0.5 -0.5 0.5 0.5 -0.5 0.5",
-			"l 1 1 0.2 0.8 -0.5 0.3 -1.5 1. -1.5 -1. -0.5 -0.3",
-			"p 1 1 1 0.2 -0.5 0.3 -1.5 1 -1.5 -1 -0.5 -0.3",
-			"l 1 1 0.2 0.8 0.5 0.3 1.5 1. 1.5 -1. 0.5 -0.3",
-			"p 1 1 1 0.2 0.5 0.3 1.5 1 1.5 -1 0.5 -0.3",
+		pub static ref SMALL1: GraphicObjects = themed_graphic_objects(vec![
+			(
+				"enemy_hull",
+				"l 0.3 0.5 0.5 1 -0.5 0.5 -0.5 -0.5 0.5 -0.5 0.5 0.5 -0.5 0.5"
+			),
+			(
+				"enemy_detail",
+				"l 1 1 0.2 0.8 -0.5 0.3 -1.5 1. -1.5 -1. -0.5 -0.3"
+			),
+			(
+				"enemy_detail",
+				"p 1 1 1 0.2 -0.5 0.3 -1.5 1 -1.5 -1 -0.5 -0.3"
+			),
+			(
+				"enemy_detail",
+				"l 1 1 0.2 0.8 0.5 0.3 1.5 1. 1.5 -1. 0.5 -0.3"
+			),
+			("enemy_detail", "p 1 1 1 0.2 0.5 0.3 1.5 1 1.5 -1 0.5 -0.3"),
 		])
 		.zoom(10.);
-		pub static ref SMALL2: GraphicObjects = GraphicObjects::from_strs(vec![
-			"l 0.3 0.5 0.5 1 0 -1 1 0 0 1 -1 0 0 -1",
-			"l 0.3 0.5 0.5 1 0.5 -0.5 2 -1 1 0",
-			"l 0.3 0.5 0.5 1 -0.5 -0.5 -2 -1 -1 0",
-			"p 0.1 0.1 1 0.2 0 1 1.5 -0.5 -1.5 -0.5",
+		pub static ref SMALL2: GraphicObjects = themed_graphic_objects(vec![
+			("enemy_hull", "l 0.3 0.5 0.5 1 0 -1 1 0 0 1 -1 0 0 -1"),
+			("enemy_detail", "l 0.3 0.5 0.5 1 0.5 -0.5 2 -1 1 0"),
+			("enemy_detail", "l 0.3 0.5 0.5 1 -0.5 -0.5 -2 -1 -1 0"),
+			("enemy_detail", "p 0.1 0.1 1 0.2 0 1 1.5 -0.5 -1.5 -0.5"),
 		])
 		.zoom(10.);
-		pub static ref SMALL3: GraphicObjects = GraphicObjects::from_strs(vec![
-			"l 0.5 0.7 1 0.7 0 1 1 -1 -1 -1 0 1",
-			"l 0.3 1 0.5 0.6 1 -1 2 0 2 1 0.3 0.4",
-			"p 0.3 0.2 1 0.2 1 -1 2 0 2 1 0.3 0.4 1 -1",
-			"l 0.3 1 0.5 0.6 -1 -1 -2 0 -2 1 -0.3 0.4",
-			"p 0.3 0.2 1 0.2 -1 -1 -2 0 -2 1 -0.3 0.4 -1 -1",
+		pub static ref SMALL3: GraphicObjects = themed_graphic_objects(vec![
+			("enemy_hull", "l 0.5 0.7 1 0.7 0 1 1 -1 -1 -1 0 1"),
+			("enemy_detail", "l 0.3 1 0.5 0.6 1 -1 2 0 2 1 0.3 0.4"),
+			("enemy_detail", "p 0.3 0.2 1 0.2 1 -1 2 0 2 1 0.3 0.4 1 -1"),
+			("enemy_detail", "l 0.3 1 0.5 0.6 -1 -1 -2 0 -2 1 -0.3 0.4"),
+			(
+				"enemy_detail",
+				"p 0.3 0.2 1 0.2 -1 -1 -2 0 -2 1 -0.3 0.4 -1 -1"
+			),
 		])
 		.zoom(9.);
-		pub static ref MEDIUM1: GraphicObjects = GraphicObjects::from_strs(vec![
-			"l 1 0.2 0.2 1 0.7 -0.6 0.7 0.1 0.2 0.8 -0.2 0.8 -0.7 0.1 -0.7 -0.6 0.7 -0.6",
-			"l 1 1 1 0.5 0.6 -0.1 1.2 -0.1 1.2 -1 3 -1 3 1 1.2 1 1.2 0.1 0.6 0.1",
-			"l 1 1 1 0.5 -0.6 -0.1 -1.2 -0.1 -1.2 -1 -3 -1 -3 1 -1.2 1 -1.2 0.1 -0.6 0.1",
-			"p 1 0.2 0.3 0.3 3 -1 3.5 -1 3.5 1 3 1",
-			"p 1 0.2 0.3 0.3 -3 -1 -3.5 -1 -3.5 1 -3 1",
+		pub static ref MEDIUM1: GraphicObjects = themed_graphic_objects(vec![
+			(
+				"enemy_hull",
+				"l 1 0.2 0.2 1 0.7 -0.6 0.7 0.1 0.2 0.8 -0.2 0.8 -0.7 0.1 -0.7 -0.6 0.7 -0.6"
+			),
+			(
+				"enemy_detail",
+				"l 1 1 1 0.5 0.6 -0.1 1.2 -0.1 1.2 -1 3 -1 3 1 1.2 1 1.2 0.1 0.6 0.1"
+			),
+			(
+				"enemy_detail",
+				"l 1 1 1 0.5 -0.6 -0.1 -1.2 -0.1 -1.2 -1 -3 -1 -3 1 -1.2 1 -1.2 0.1 -0.6 0.1"
+			),
+			("enemy_detail", "p 1 0.2 0.3 0.3 3 -1 3.5 -1 3.5 1 3 1"),
+			("enemy_detail", "p 1 0.2 0.3 0.3 -3 -1 -3.5 -1 -3.5 1 -3 1"),
 		])
 		.zoom(15.);
-		pub static ref LARGE1: GraphicObjects = GraphicObjects::from_strs(vec![
-			"l 0.8 0.8 1 0.8 2.4 0.5 2.4 -1 -2.4 -1 -2.4 0.5 -3.2 1.5 3.2 1.5 2.4 0.5",
-			"p 0.8 0.8 1 0.2 2.4 0.5 2.4 -1 -2.4 -1 -2.4 0.5 -3.2 1.5 3.2 1.5",
-			"l 1 1 1 0.7 2.4 0 4 0",
-			"l 1 1 1 0.7 2.4 0.5 4 0.5",
-			"l 1 1 1 0.7 -2.4 0 -4 0",
-			"l 1 1 1 0.7 -2.4 0.5 -4 0.5",
-			"l 1 0.9 0.7 1 -4 0 -6 -2 -6 -1 -5 0.5 -7 3 -5 3 -4 0.5 -4 0",
-			"p 1 0.8 0.6 0.2 -4 0 -6 -2 -6 -1 -5 0.5 -7 3 -5 3 -4 0.5",
-			"l 1 0.9 0.7 1 4 0 6 -2 6 -1 5 0.5 7 3 5 3 4 0.5 4 0",
-			"p 1 0.8 0.6 0.2 4 0 6 -2 6 -1 5 0.5 7 3 5 3 4 0.5",
-			"l 1 1 1 1 3.2 1.5 3.2 2.5 -3.2 2.5 -3.2 1.5 3.2 1.5",
+		pub static ref LARGE1: GraphicObjects = themed_graphic_objects(vec![
+			(
+				"enemy_hull",
+				"l 0.8 0.8 1 0.8 2.4 0.5 2.4 -1 -2.4 -1 -2.4 0.5 -3.2 1.5 3.2 1.5 2.4 0.5"
+			),
+			(
+				"enemy_hull",
+				"p 0.8 0.8 1 0.2 2.4 0.5 2.4 -1 -2.4 -1 -2.4 0.5 -3.2 1.5 3.2 1.5"
+			),
+			("enemy_detail", "l 1 1 1 0.7 2.4 0 4 0"),
+			("enemy_detail", "l 1 1 1 0.7 2.4 0.5 4 0.5"),
+			("enemy_detail", "l 1 1 1 0.7 -2.4 0 -4 0"),
+			("enemy_detail", "l 1 1 1 0.7 -2.4 0.5 -4 0.5"),
+			(
+				"enemy_detail",
+				"l 1 0.9 0.7 1 -4 0 -6 -2 -6 -1 -5 0.5 -7 3 -5 3 -4 0.5 -4 0"
+			),
+			(
+				"enemy_detail",
+				"p 1 0.8 0.6 0.2 -4 0 -6 -2 -6 -1 -5 0.5 -7 3 -5 3 -4 0.5"
+			),
+			(
+				"enemy_detail",
+				"l 1 0.9 0.7 1 4 0 6 -2 6 -1 5 0.5 7 3 5 3 4 0.5 4 0"
+			),
+			(
+				"enemy_detail",
+				"p 1 0.8 0.6 0.2 4 0 6 -2 6 -1 5 0.5 7 3 5 3 4 0.5"
+			),
+			(
+				"enemy_hull",
+				"l 1 1 1 1 3.2 1.5 3.2 2.5 -3.2 2.5 -3.2 1.5 3.2 1.5"
+			),
 		])
 		.zoom(15.);
 	}
@@ -140,6 +188,15 @@ pub mod enemy_prototype {
 			cannon_pool: 1,
 		};
 	}
+
+	pub fn from_name(name: &str) -> Option<EnemyPrototype> {
+		match name {
+			"small" => Some(SMALL.clone()),
+			"medium" => Some(MEDIUM.clone()),
+			"large1" => Some(LARGE1.clone()),
+			_ => None,
+		}
+	}
 }
 
 #[derive(Clone)]
@@ -154,8 +211,15 @@ pub struct Enemy {
 	cannons: Vec<Box<dyn CannonControllerInterface>>,
 	graphic_objects: GraphicObjects,
 	hitboxes: Vec<Circle2f>,
+
+	// sound of each cannon with cooldown
+	cannon_sounds: Vec<(Arc<CannonSound>, f32)>,
+	fired_sounds: Vec<Arc<CannonSound>>,
 }
 
+// minimum interval of sounds from one cannon
+const CANNON_SOUND_INTERVAL: f32 = 0.25;
+
 impl Enemy {
 	pub fn new(
 		path: EnemyPath,
@@ -165,6 +229,16 @@ impl Enemy {
 		graphic_objects: GraphicObjects,
 		hitboxes: Vec<Circle2f>,
 	) -> Enemy {
+		let cannon_sounds = cannons
+			.iter()
+			.map(|cannon| {
+				let sound = CannonSound {
+					cannon_type: cannon.cannon_type(),
+					parameters: cannon.parameters(),
+				};
+				(Arc::new(sound), 0.)
+			})
+			.collect();
 		Enemy {
 			p: None,
 			last_p: None,
@@ -175,6 +249,8 @@ impl Enemy {
 			cannons,
 			graphic_objects,
 			hitboxes,
+			cannon_sounds,
+			fired_sounds: Vec::new(),
 		}
 	}
 
@@ -194,9 +270,21 @@ impl Enemy {
 		self.life -= self.life_autodrop * dt;
 
 		let mut bullet_queue = VecDeque::new();
+		self.fired_sounds.clear();
 		// path is executed before update_theta so unwrap p should be safe
-		for cannon in self.cannons.iter_mut() {
-			bullet_queue.extend(cannon.tick(self.p.unwrap(), player_p, dt));
+		for (cannon, (sound, sound_cd)) in
+			self.cannons.iter_mut().zip(self.cannon_sounds.iter_mut())
+		{
+			let mut bullets = cannon.tick(self.p.unwrap(), player_p, dt);
+			for bullet in bullets.iter_mut() {
+				bullet.set_cannon_type(cannon.cannon_type());
+			}
+			*sound_cd -= dt;
+			if !bullets.is_empty() && *sound_cd <= 0. {
+				self.fired_sounds.push(sound.clone());
+				*sound_cd = CANNON_SOUND_INTERVAL;
+			}
+			bullet_queue.extend(bullets);
 		}
 		EnemyTickReturnOption::Normal(bullet_queue)
 	}
@@ -213,6 +301,11 @@ impl Enemy {
 		self.last_p
 	}
 
+	// sounds of cannons fired in last tick
+	pub fn get_fired_sounds(&self) -> &[Arc<CannonSound>] {
+		&self.fired_sounds
+	}
+
 	pub fn get_hitboxes(&self) -> &Vec<Circle2f> {
 		&self.hitboxes
 	}
@@ -111,30 +111,52 @@ impl EnemyPath {
 		}
 	}
 
+	// stays at p with cannons on, long enough for a sandbox session
+	pub fn stationary(p: Point2f) -> EnemyPath {
+		const DURATION: f32 = 1e6;
+		EnemyPath {
+			route: vec![(p, DURATION, true), (p, 0., true)],
+			index: 0,
+			timer: 0.,
+		}
+	}
+
+	// for built-in paths, which are known to be valid
 	pub fn from_str(line: &str) -> EnemyPath {
-		let mut route = Vec::new();
+		EnemyPath::try_from_str(line).unwrap()
+	}
+
+	// "x y duration switch ...", x and y relative to window size,
+	// at least two points since the last duration is not used
+	pub fn try_from_str(line: &str) -> Result<EnemyPath, String> {
 		let splited = line
 			.split_whitespace()
-			.map(|x| x.parse::<f32>().expect("float parse fail"))
-			.collect::<Vec<f32>>();
-		let mut iter = splited.iter();
-		while match iter.next() {
-			Some(f1) => {
-				route.push((
-					Point2f::from_floats(*f1, *iter.next().unwrap()) * *WINDOW_SIZE
-						+ WINDOW_RECT.lu,
-					*iter.next().unwrap(),
-					*iter.next().unwrap() > 0.5, // actually it is equal 1.0
-				));
-				true
-			}
-			None => false,
-		} {}
-		EnemyPath {
+			.map(|x| {
+				x.parse::<f32>()
+					.map_err(|e| format!("path \"{}\": {}", line, e))
+			})
+			.collect::<Result<Vec<f32>, String>>()?;
+		if splited.len() % 4 != 0 || splited.len() < 8 {
+			return Err(format!(
+				"path \"{}\" should have at least two points of 4 numbers",
+				line
+			));
+		}
+		let route = splited
+			.chunks(4)
+			.map(|point| {
+				(
+					Point2f::from_floats(point[0], point[1]) * *WINDOW_SIZE + WINDOW_RECT.lu,
+					point[2],
+					point[3] > 0.5, // actually it is equal 1.0
+				)
+			})
+			.collect();
+		Ok(EnemyPath {
 			route,
 			index: 0,
 			timer: 0.,
-		}
+		})
 	}
 
 	// return None if path ends, or pos and switch
@@ -159,3 +181,18 @@ impl EnemyPath {
 		}
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_try_from_str() {
+		let path = EnemyPath::try_from_str("0.5 0 9 1 0.5 1 0 0").unwrap();
+		assert_eq!(path.route.len(), 2);
+		assert!(path.route[0].2);
+		assert!(EnemyPath::try_from_str("0.5 0 9 1 0.5 1 0").is_err());
+		assert!(EnemyPath::try_from_str("0.5 0 9 1").is_err());
+		assert!(EnemyPath::try_from_str("0.5 0 9 x 0.5 1 0 0").is_err());
+	}
+}
@@ -1,19 +1,25 @@
 use std::collections::VecDeque;
+use std::sync::Arc;
 
+use crate::agent::{point, CircleObservation, EnemyObservation};
 use crate::algebra::Point2f;
+use crate::audio::CannonSound;
 use crate::bullet::Bullet;
-use crate::collision::CollisionPipeInterface;
+use crate::collision::{CollisionPipeInterface, SweptCircle};
 use crate::enemy::{Enemy, EnemyTickReturnOption};
 use crate::graphic_object::{GraphicObjects, GraphicObjectsIntoIter};
 
 pub struct EnemyPool {
 	enemies: VecDeque<Enemy>,
+	// cannon sounds of last tick
+	fired_sounds: Vec<Arc<CannonSound>>,
 }
 
 impl EnemyPool {
 	pub fn new() -> EnemyPool {
 		EnemyPool {
 			enemies: VecDeque::new(),
+			fired_sounds: Vec::new(),
 		}
 	}
 
@@ -24,12 +30,15 @@ impl EnemyPool {
 	pub fn tick(&mut self, dt: f32, player_p: Point2f) -> VecDeque<Box<dyn Bullet>> {
 		let len = self.enemies.len();
 		let mut bullet_queue_return = VecDeque::new();
+		self.fired_sounds.clear();
 		for _ in 0..len {
 			let mut enemy = self.enemies.pop_front().unwrap();
 			// update pos
 			match enemy.tick(dt, player_p) {
 				EnemyTickReturnOption::Normal(bullet_queue) => {
 					bullet_queue_return.extend(bullet_queue);
+					self.fired_sounds
+						.extend(enemy.get_fired_sounds().iter().cloned());
 					self.enemies.push_back(enemy);
 				}
 				EnemyTickReturnOption::Removed => {}
@@ -39,6 +48,46 @@ impl EnemyPool {
 		bullet_queue_return
 	}
 
+	pub fn get_fired_sounds(&self) -> &[Arc<CannonSound>] {
+		&self.fired_sounds
+	}
+
+	// hitboxes for debug overlay
+	pub fn swept_circles(&self) -> Vec<SweptCircle> {
+		let mut swept_circles = Vec::new();
+		for enemy in self.enemies.iter() {
+			if let (Some(p), Some(last_p)) = (enemy.get_p(), enemy.get_last_p()) {
+				for hitbox in enemy.get_hitboxes().iter() {
+					swept_circles.push(SweptCircle {
+						p: p + hitbox.center,
+						last_p: last_p + hitbox.center,
+						r: hitbox.r,
+					});
+				}
+			}
+		}
+		swept_circles
+	}
+
+	pub fn observations(&self) -> Vec<EnemyObservation> {
+		self.enemies
+			.iter()
+			.filter_map(|enemy| {
+				enemy.get_p().map(|p| EnemyObservation {
+					p: point(p),
+					hitboxes: enemy
+						.get_hitboxes()
+						.iter()
+						.map(|hitbox| CircleObservation {
+							p: point(p + hitbox.center),
+							r: hitbox.r,
+						})
+						.collect(),
+				})
+			})
+			.collect()
+	}
+
 	pub fn graphic_objects_iter(&self) -> GraphicObjectsIntoIter {
 		let mut graphic_objects: GraphicObjects = Default::default();
 		for enemy in self.enemies.iter() {
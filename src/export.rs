@@ -0,0 +1,191 @@
+use std::io::Write;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::display::Display;
+use crate::key_binding::Action;
+use crate::png_writer::save_png;
+use crate::record::Record;
+use crate::session::Session;
+
+pub fn subcommand() -> App<'static, 'static> {
+	SubCommand::with_name("export")
+		.about("play a replay headless and write frames to y4m or png sequence")
+		.arg(Arg::with_name("replay").required(true).help("replay file"))
+		.arg(
+			Arg::with_name("out")
+				.short("o")
+				.long("out")
+				.takes_value(true)
+				.required(true)
+				.help("*.y4m file, \"-\" for y4m to stdout, or a directory for png sequence"),
+		)
+		.arg(
+			Arg::with_name("fps")
+				.long("fps")
+				.takes_value(true)
+				.help("output frame rate, default 60"),
+		)
+		.arg(
+			Arg::with_name("scale")
+				.long("scale")
+				.takes_value(true)
+				.help("canvas scaler, default 1"),
+		)
+		.arg(
+			Arg::with_name("hitboxes")
+				.long("hitboxes")
+				.help("show hitbox debug overlay"),
+		)
+		.arg(
+			Arg::with_name("panel")
+				.long("panel")
+				.help("show side panel"),
+		)
+		.arg(
+			Arg::with_name("retro")
+				.long("retro")
+				.help("retro vector-line style, with phosphor trails of output frames"),
+		)
+}
+
+// uncompressed 4:2:0 YUV, BT.601 limited range
+pub struct Y4mWriter<W: Write> {
+	writer: W,
+	size: (usize, usize),
+}
+
+impl<W: Write> Y4mWriter<W> {
+	pub fn new(mut writer: W, size: (usize, usize), fps: u32) -> std::io::Result<Y4mWriter<W>> {
+		writeln!(
+			writer,
+			"YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C420jpeg",
+			size.0, size.1, fps
+		)?;
+		Ok(Y4mWriter { writer, size })
+	}
+
+	// rgb is RGB24
+	pub fn write_frame(&mut self, rgb: &[u8]) -> std::io::Result<()> {
+		let (w, h) = self.size;
+		let (cw, ch) = (w.div_ceil(2), h.div_ceil(2));
+		let mut y_plane = Vec::with_capacity(w * h);
+		let mut u_plane = vec![0f32; cw * ch];
+		let mut v_plane = vec![0f32; cw * ch];
+		let mut counts = vec![0f32; cw * ch];
+		for y in 0..h {
+			for x in 0..w {
+				let offset = (y * w + x) * 3;
+				let (r, g, b) = (
+					rgb[offset] as f32,
+					rgb[offset + 1] as f32,
+					rgb[offset + 2] as f32,
+				);
+				y_plane.push((16. + (65.481 * r + 128.553 * g + 24.966 * b) / 255.).round() as u8);
+				let c = (y / 2) * cw + x / 2;
+				u_plane[c] += 128. + (-37.797 * r - 74.203 * g + 112. * b) / 255.;
+				v_plane[c] += 128. + (112. * r - 93.786 * g - 18.214 * b) / 255.;
+				counts[c] += 1.;
+			}
+		}
+		self.writer.write_all(b"FRAME\n")?;
+		self.writer.write_all(&y_plane)?;
+		let average = |plane: Vec<f32>| -> Vec<u8> {
+			plane
+				.iter()
+				.zip(counts.iter())
+				.map(|(sum, count)| (sum / count).round() as u8)
+				.collect()
+		};
+		self.writer.write_all(&average(u_plane))?;
+		self.writer.write_all(&average(v_plane))
+	}
+}
+
+enum Output {
+	Y4m(Y4mWriter<std::io::BufWriter<Box<dyn Write>>>),
+	// directory
+	Png(String),
+}
+
+pub fn run(matches: &ArgMatches) {
+	let fps = matches
+		.value_of("fps")
+		.map(|fps| fps.parse::<u32>().unwrap())
+		.unwrap_or(60);
+	let out = matches.value_of("out").unwrap();
+	let scaler = matches
+		.value_of("scale")
+		.map(|scale| scale.parse::<f32>().unwrap())
+		.unwrap_or(1.);
+	let mut display = Display::new(
+		scaler,
+		matches.is_present("retro"),
+		matches.is_present("panel"),
+	);
+	let (width, height) = display.get_size();
+	let size = (width as usize, height as usize);
+	let mut output = if out == "-" || out.ends_with(".y4m") {
+		let writer: Box<dyn Write> = if out == "-" {
+			Box::new(std::io::stdout())
+		} else {
+			Box::new(std::fs::File::create(out).unwrap())
+		};
+		Output::Y4m(Y4mWriter::new(std::io::BufWriter::new(writer), size, fps).unwrap())
+	} else {
+		std::fs::create_dir_all(out).unwrap();
+		Output::Png(out.to_string())
+	};
+
+	let replay = matches.value_of("replay").unwrap();
+	let mut session = Session::from_record(
+		Record::load(replay.to_string())
+			.unwrap_or_else(|e| panic!("Failed to load replay {}: {}", replay, e)),
+	);
+	session.set_headless(true);
+	if matches.is_present("hitboxes") {
+		session.proc_key(Action::DebugOverlay.key_id().unwrap(), true);
+	}
+	// frame n shows the first tick after n / fps seconds of recorded time
+	let mut frame = 0;
+	'export: loop {
+		while session.get_time() <= frame as f32 / fps as f32 {
+			if !session.tick(0.) {
+				break 'export;
+			}
+		}
+		display.begin_frame(1. / fps as f32);
+		session.render(&mut display);
+		let data = display.get_data();
+		match &mut output {
+			Output::Y4m(y4m_writer) => y4m_writer.write_frame(data).unwrap(),
+			Output::Png(dir) => {
+				let filename = format!("{}/{:06}.png", dir, frame);
+				save_png(&filename, (width, height), data)
+					.unwrap_or_else(|e| panic!("Failed to write {}: {}", filename, e));
+			}
+		}
+		frame += 1;
+	}
+	// stdout may carry y4m stream
+	eprintln!("{} frames exported", frame);
+}
+
+#[cfg(test)]
+mod test {
+	use super::Y4mWriter;
+
+	#[test]
+	fn test_y4m_frame() {
+		let mut buffer = Vec::new();
+		let mut writer = Y4mWriter::new(&mut buffer, (3, 1), 60).unwrap();
+		// white, black, red
+		writer
+			.write_frame(&[255, 255, 255, 0, 0, 0, 255, 0, 0])
+			.unwrap();
+		let header = b"YUV4MPEG2 W3 H1 F60:1 Ip A1:1 C420jpeg\nFRAME\n";
+		assert_eq!(&buffer[..header.len()], &header[..]);
+		// y plane has 3 samples, chroma planes have 2 samples each
+		assert_eq!(&buffer[header.len()..], &[235, 16, 81, 128, 90, 128, 240]);
+	}
+}
@@ -1,5 +1,6 @@
 pub struct FileManager {
 	eyhv_data_dir: String,
+	eyhv_config_dir: String,
 }
 
 impl FileManager {
@@ -12,10 +13,64 @@ impl FileManager {
 			},
 		};
 		std::fs::create_dir_all(eyhv_data_dir.clone()).unwrap();
-		FileManager { eyhv_data_dir }
+		let eyhv_config_dir: String = match std::env::var("XDG_CONFIG_HOME") {
+			Ok(val) => val + "/eyhv",
+			_ => match std::env::var("HOME") {
+				Ok(val) => val + "/.config/eyhv",
+				_ => panic!("Cannot find config directory"),
+			},
+		};
+		std::fs::create_dir_all(eyhv_config_dir.clone()).unwrap();
+		FileManager {
+			eyhv_data_dir,
+			eyhv_config_dir,
+		}
+	}
+
+	pub fn get_config_path(&self, filename: &str) -> String {
+		self.eyhv_config_dir.clone() + "/" + filename
 	}
 
 	pub fn get_replay_path(&self) -> String {
 		return self.eyhv_data_dir.clone() + "/latest_replay";
 	}
+
+	pub fn get_practice_replay_path(&self) -> String {
+		self.eyhv_data_dir.clone() + "/latest_practice"
+	}
+
+	// agent episodes are replays named by unix time in milliseconds, episode and seed
+	pub fn get_agent_replay_path(&self, episode: u32, seed: u64) -> String {
+		let millis = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.unwrap()
+			.as_millis();
+		format!(
+			"{}/agent_{}_{}_{}",
+			self.eyhv_data_dir, millis, episode, seed
+		)
+	}
+
+	// named by unix time in milliseconds
+	pub fn get_screenshot_path(&self) -> String {
+		let millis = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.unwrap()
+			.as_millis();
+		format!("{}/screenshot_{}.png", self.eyhv_data_dir, millis)
+	}
+
+	// replays are the files without extension in data dir
+	pub fn list_replays(&self) -> Vec<String> {
+		let mut replays: Vec<String> = match std::fs::read_dir(&self.eyhv_data_dir) {
+			Ok(entries) => entries
+				.filter_map(|entry| entry.ok())
+				.filter(|entry| entry.path().is_file() && entry.path().extension().is_none())
+				.map(|entry| entry.path().to_string_lossy().to_string())
+				.collect(),
+			Err(_) => Vec::new(),
+		};
+		replays.sort();
+		replays
+	}
 }
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+// gameplay tunables, saved in every record
+// fields missing in a rules file take default values
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct GameRules {
+	// slowdown pools, in seconds
+	pub quick_max: f32,
+	pub slow_max: f32,
+	pub quick_replenish_rate: f32,
+	pub slow_replenish_rate: f32,
+
+	// world time scaler
+	pub shift_rate_down: f32,
+	pub shift_rate_up: f32,
+	pub dt_scaler_slow: f32,
+
+	// player
+	pub speed_fast: f32,
+	pub speed_slow: f32,
+	pub hit_reset: f32,
+	pub player_hitbox_r: f32,
+}
+
+impl Default for GameRules {
+	fn default() -> GameRules {
+		GameRules {
+			quick_max: 1.,
+			slow_max: 8.,
+			quick_replenish_rate: 1.,
+			slow_replenish_rate: 0.3,
+			shift_rate_down: 2.5,
+			shift_rate_up: 5.,
+			dt_scaler_slow: 0.5,
+			speed_fast: 1000.,
+			speed_slow: 500.,
+			hit_reset: 0.15,
+			player_hitbox_r: 5.,
+		}
+	}
+}
+
+pub const PRESETS: [&str; 3] = ["standard", "relaxed", "hardcore"];
+
+impl GameRules {
+	pub fn preset(name: &str) -> Option<GameRules> {
+		match name {
+			"standard" => Some(Default::default()),
+			"relaxed" => Some(GameRules {
+				quick_max: 2.,
+				slow_max: 12.,
+				quick_replenish_rate: 1.5,
+				slow_replenish_rate: 0.5,
+				dt_scaler_slow: 0.4,
+				hit_reset: 0.3,
+				player_hitbox_r: 3.,
+				..Default::default()
+			}),
+			"hardcore" => Some(GameRules {
+				slow_max: 4.,
+				slow_replenish_rate: 0.15,
+				dt_scaler_slow: 0.6,
+				hit_reset: 0.1,
+				player_hitbox_r: 6.,
+				..Default::default()
+			}),
+			_ => None,
+		}
+	}
+
+	pub fn load(filename: &str) -> Result<GameRules, String> {
+		let string = std::fs::read_to_string(filename).map_err(|e| e.to_string())?;
+		toml::from_str(&string).map_err(|e| e.to_string())
+	}
+
+	// arg is either a preset name or a rules file
+	pub fn from_arg(arg: &str) -> Result<GameRules, String> {
+		match GameRules::preset(arg) {
+			Some(rules) => Ok(rules),
+			None => GameRules::load(arg),
+		}
+	}
+}
@@ -0,0 +1,234 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::algebra::Point2f;
+use crate::display::Display;
+use crate::graphic_object::{GraphicObjects, Polygon2f};
+use crate::record::Record;
+use crate::screenshot::save_canvas;
+use crate::session::Session;
+use crate::text::text_graphic_objects;
+use crate::window_rect::{WINDOW_RECT, WINDOW_SIZE};
+
+pub fn subcommand() -> App<'static, 'static> {
+	SubCommand::with_name("heatmap")
+		.about("aggregate replays into heatmaps of player hits and time spent")
+		.arg(
+			Arg::with_name("replays")
+				.required(true)
+				.multiple(true)
+				.help("replay files"),
+		)
+		.arg(
+			Arg::with_name("out")
+				.short("o")
+				.long("out")
+				.takes_value(true)
+				.help("output prefix, writes <out>_hits.png and <out>_time.png, default heatmap"),
+		)
+		.arg(
+			Arg::with_name("cell")
+				.long("cell")
+				.takes_value(true)
+				.help("cell size in pixels, default 10"),
+		)
+		.arg(
+			Arg::with_name("scale")
+				.long("scale")
+				.takes_value(true)
+				.help("canvas scaler, default 1"),
+		)
+}
+
+// accumulated values over the playfield
+pub struct Heatmap {
+	cell: f32,
+	width: usize,
+	height: usize,
+	values: Vec<f32>,
+}
+
+impl Heatmap {
+	pub fn new(cell: f32) -> Heatmap {
+		let width = (WINDOW_SIZE.x / cell).ceil() as usize;
+		let height = (WINDOW_SIZE.y / cell).ceil() as usize;
+		Heatmap {
+			cell,
+			width,
+			height,
+			values: vec![0.; width * height],
+		}
+	}
+
+	fn add_at(&mut self, x: i32, y: i32, value: f32) {
+		if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+			self.values[y as usize * self.width + x as usize] += value;
+		}
+	}
+
+	pub fn add(&mut self, p: Point2f, value: f32) {
+		let p = p - WINDOW_RECT.lu;
+		self.add_at((p.x / self.cell) as i32, (p.y / self.cell) as i32, value);
+	}
+
+	// sparse points are spread to neighbor cells to be visible
+	pub fn splat(&mut self, p: Point2f, value: f32) {
+		let p = p - WINDOW_RECT.lu;
+		let (x, y) = ((p.x / self.cell) as i32, (p.y / self.cell) as i32);
+		for dx in -1..=1 {
+			for dy in -1..=1 {
+				let weight = if dx == 0 && dy == 0 {
+					1.
+				} else if dx == 0 || dy == 0 {
+					0.5
+				} else {
+					0.25
+				};
+				self.add_at(x + dx, y + dy, value * weight);
+			}
+		}
+	}
+
+	// black, blue, red, yellow, white
+	fn color(k: f32) -> [f32; 4] {
+		const STOPS: [[f32; 3]; 5] = [
+			[0., 0., 0.],
+			[0.1, 0.1, 0.8],
+			[0.9, 0.1, 0.1],
+			[1., 0.9, 0.1],
+			[1., 1., 1.],
+		];
+		let k = k.clamp(0., 1.) * (STOPS.len() - 1) as f32;
+		let i = (k as usize).min(STOPS.len() - 2);
+		let t = k - i as f32;
+		let mut color = [1.; 4];
+		for c in 0..3 {
+			color[c] = STOPS[i][c] * (1. - t) + STOPS[i + 1][c] * t;
+		}
+		color
+	}
+
+	// values are normalized by max, gamma < 1 lifts small values
+	pub fn graphic_objects(&self, gamma: f32) -> GraphicObjects {
+		let max = self.values.iter().cloned().fold(0., f32::max);
+		let mut graphic_objects: GraphicObjects = Default::default();
+		if max <= 0. {
+			return graphic_objects;
+		}
+		for y in 0..self.height {
+			for x in 0..self.width {
+				let value = self.values[y * self.width + x];
+				if value <= 0. {
+					continue;
+				}
+				let color = Heatmap::color((value / max).powf(gamma));
+				let lu = WINDOW_RECT.lu + Point2f::from_floats(x as f32, y as f32) * self.cell;
+				let rd = lu + Point2f::from_floats(self.cell, self.cell);
+				graphic_objects.push(Box::new(Polygon2f {
+					vertices: vec![
+						lu,
+						Point2f::from_floats(rd.x, lu.y),
+						rd,
+						Point2f::from_floats(lu.x, rd.y),
+					],
+					color,
+					border_color: color,
+				}));
+			}
+		}
+		graphic_objects
+	}
+}
+
+// plays a replay headless, adding time spent and hit positions
+fn accumulate(record: Record, time: &mut Heatmap, hits: &mut Heatmap) -> (f32, u32) {
+	let mut session = Session::from_record(record);
+	session.set_headless(true);
+	let mut last_time = 0.;
+	let mut last_hits = 0;
+	while session.tick(0.) {
+		let p = session.get_player_p();
+		time.add(p, session.get_time() - last_time);
+		last_time = session.get_time();
+		if session.get_hits() > last_hits {
+			hits.splat(p, (session.get_hits() - last_hits) as f32);
+			last_hits = session.get_hits();
+		}
+	}
+	(last_time, last_hits)
+}
+
+fn save(filename: &str, scaler: f32, heatmap: &Heatmap, gamma: f32, label: &str) {
+	let mut display = Display::new(scaler, false, false);
+	display.begin_frame(0.);
+	display.draw(heatmap.graphic_objects(gamma).into_iter());
+	display.draw(
+		text_graphic_objects(
+			label,
+			12.,
+			Point2f::from_floats(10., 10.),
+			Some([1., 1., 1., 0.8]),
+		)
+		.into_iter(),
+	);
+	save_canvas(filename, display.get_size(), display.get_data());
+	println!("{} written", filename);
+}
+
+pub fn run(matches: &ArgMatches) {
+	let out = matches.value_of("out").unwrap_or("heatmap");
+	let cell = matches
+		.value_of("cell")
+		.map(|cell| cell.parse::<f32>().unwrap())
+		.unwrap_or(10.);
+	let scaler = matches
+		.value_of("scale")
+		.map(|scale| scale.parse::<f32>().unwrap())
+		.unwrap_or(1.);
+	let mut time = Heatmap::new(cell);
+	let mut hits = Heatmap::new(cell);
+	let mut total_time = 0.;
+	let mut total_hits = 0;
+	for replay in matches.values_of("replays").unwrap() {
+		let record = Record::load(replay.to_string())
+			.unwrap_or_else(|e| panic!("Failed to load replay {}: {}", replay, e));
+		let (replay_time, replay_hits) = accumulate(record, &mut time, &mut hits);
+		println!("{}: {:.1}s, {} hits", replay, replay_time, replay_hits);
+		total_time += replay_time;
+		total_hits += replay_hits;
+	}
+	save(
+		&format!("{}_time.png", out),
+		scaler,
+		&time,
+		0.5,
+		&format!("TIME {:.0}S", total_time),
+	);
+	save(
+		&format!("{}_hits.png", out),
+		scaler,
+		&hits,
+		1.,
+		&format!("HITS {}", total_hits),
+	);
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_heatmap() {
+		let mut heatmap = Heatmap::new(10.);
+		assert_eq!((heatmap.width, heatmap.height), (50, 70));
+		heatmap.add(Point2f::from_floats(15., 25.), 2.);
+		assert_eq!(heatmap.values[2 * 50 + 1], 2.);
+		// outside is ignored
+		heatmap.splat(Point2f::from_floats(0., 0.), 1.);
+		assert_eq!(
+			heatmap.values.iter().sum::<f32>(),
+			2. + 1. + 0.5 + 0.5 + 0.25
+		);
+		assert_eq!(Heatmap::color(0.), [0., 0., 0., 1.]);
+		assert_eq!(Heatmap::color(1.), [1., 1., 1., 1.]);
+	}
+}
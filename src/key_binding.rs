@@ -0,0 +1,292 @@
+use std::collections::{BTreeMap, HashMap};
+
+use sdl2::keyboard::Keycode;
+
+use crate::file_manager::FileManager;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+	Left,
+	Up,
+	Right,
+	Down,
+	Slowdown,
+	Fire,
+	Pause,
+	FastForward,
+	FpsIndicator,
+	DebugOverlay,
+	Screenshot,
+	Fullscreen,
+	RetroMode,
+	SidePanel,
+	Quit,
+}
+
+const ACTIONS: [Action; 15] = [
+	Action::Left,
+	Action::Up,
+	Action::Right,
+	Action::Down,
+	Action::Slowdown,
+	Action::Fire,
+	Action::Pause,
+	Action::FastForward,
+	Action::FpsIndicator,
+	Action::DebugOverlay,
+	Action::Screenshot,
+	Action::Fullscreen,
+	Action::RetroMode,
+	Action::SidePanel,
+	Action::Quit,
+];
+
+impl Action {
+	// key ids are saved in Record::operation, never renumber them
+	pub fn key_id(self) -> Option<i8> {
+		match self {
+			Action::Left => Some(0),
+			Action::Up => Some(1),
+			Action::Right => Some(2),
+			Action::Down => Some(3),
+			Action::Slowdown => Some(4),
+			Action::Fire => Some(5),
+			Action::Pause => Some(6),
+			Action::FastForward => Some(7),
+			Action::FpsIndicator => Some(8),
+			Action::DebugOverlay => Some(9),
+			// handled in main, not recorded
+			Action::Screenshot => None,
+			Action::Fullscreen => None,
+			Action::RetroMode => None,
+			Action::SidePanel => None,
+			Action::Quit => None,
+		}
+	}
+
+	// toggles are triggered by key down only
+	pub fn has_release(self) -> bool {
+		!matches!(
+			self,
+			Action::Pause
+				| Action::FpsIndicator
+				| Action::DebugOverlay
+				| Action::Screenshot
+				| Action::Fullscreen
+				| Action::RetroMode
+				| Action::SidePanel
+				| Action::Quit
+		)
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Action::Left => "left",
+			Action::Up => "up",
+			Action::Right => "right",
+			Action::Down => "down",
+			Action::Slowdown => "slowdown",
+			Action::Fire => "fire",
+			Action::Pause => "pause",
+			Action::FastForward => "fast_forward",
+			Action::FpsIndicator => "fps_indicator",
+			Action::DebugOverlay => "debug_overlay",
+			Action::Screenshot => "screenshot",
+			Action::Fullscreen => "fullscreen",
+			Action::RetroMode => "retro_mode",
+			Action::SidePanel => "side_panel",
+			Action::Quit => "quit",
+		}
+	}
+
+	fn from_name(name: &str) -> Option<Action> {
+		ACTIONS.iter().copied().find(|action| action.name() == name)
+	}
+
+	fn default_keys(self) -> Vec<Keycode> {
+		match self {
+			Action::Left => vec![Keycode::Left],
+			Action::Up => vec![Keycode::Up],
+			Action::Right => vec![Keycode::Right],
+			Action::Down => vec![Keycode::Down],
+			Action::Slowdown => vec![Keycode::LShift],
+			Action::Fire => vec![Keycode::Z],
+			Action::Pause => vec![Keycode::LAlt],
+			Action::FastForward => vec![Keycode::Space],
+			Action::FpsIndicator => vec![Keycode::F],
+			Action::DebugOverlay => vec![Keycode::F3],
+			Action::Screenshot => vec![Keycode::F12],
+			Action::Fullscreen => vec![Keycode::F11],
+			Action::RetroMode => vec![Keycode::F10],
+			Action::SidePanel => vec![Keycode::F9],
+			Action::Quit => vec![Keycode::Q],
+		}
+	}
+}
+
+// several keys can be bound to one action, but not vice versa
+pub struct KeyBindings {
+	bindings: HashMap<Keycode, Action>,
+}
+
+impl KeyBindings {
+	fn default_table() -> BTreeMap<String, Vec<String>> {
+		ACTIONS
+			.iter()
+			.map(|action| {
+				(
+					action.name().to_string(),
+					action
+						.default_keys()
+						.iter()
+						.map(|keycode| keycode.name())
+						.collect(),
+				)
+			})
+			.collect()
+	}
+
+	fn from_table(table: BTreeMap<String, Vec<String>>) -> KeyBindings {
+		let mut bindings = HashMap::new();
+		// actions not mentioned in table keep default keys
+		for action in ACTIONS.iter() {
+			if !table.contains_key(action.name()) {
+				for keycode in action.default_keys() {
+					bindings.insert(keycode, *action);
+				}
+			}
+		}
+		for (name, key_names) in table.iter() {
+			let action = match Action::from_name(name) {
+				Some(action) => action,
+				None => {
+					println!("Unknown action \"{}\" in key bindings", name);
+					continue;
+				}
+			};
+			for key_name in key_names.iter() {
+				match Keycode::from_name(key_name) {
+					Some(keycode) => {
+						if let Some(old_action) = bindings.insert(keycode, action) {
+							println!(
+								"Key \"{}\" is bound to both {} and {}",
+								key_name,
+								old_action.name(),
+								name
+							);
+						}
+					}
+					None => println!("Unknown key \"{}\" in key bindings", key_name),
+				}
+			}
+		}
+		KeyBindings { bindings }
+	}
+
+	// load keys.toml from config dir, create one with default bindings if not exist
+	pub fn load(file_manager: &FileManager) -> KeyBindings {
+		let path = file_manager.get_config_path("keys.toml");
+		let table = match std::fs::read_to_string(&path) {
+			Ok(string) => match toml::from_str(&string) {
+				Ok(table) => table,
+				Err(e) => {
+					println!("Failed to parse {}: {}, using default keys", path, e);
+					KeyBindings::default_table()
+				}
+			},
+			Err(_) => {
+				let table = KeyBindings::default_table();
+				if std::fs::write(&path, toml::to_string(&table).unwrap()).is_err() {
+					println!("Failed to write default key bindings to {}", path);
+				}
+				table
+			}
+		};
+		KeyBindings::from_table(table)
+	}
+
+	pub fn get(&self, keycode: Keycode) -> Option<Action> {
+		self.bindings.get(&keycode).copied()
+	}
+}
+
+// keys held down, so that an action bound to several keys
+// is pressed by its first key and released by its last key
+pub struct HeldKeys {
+	keys: HashMap<Keycode, Action>,
+}
+
+impl HeldKeys {
+	pub fn new() -> HeldKeys {
+		HeldKeys {
+			keys: HashMap::new(),
+		}
+	}
+
+	// return true if no other key of action is held, false on key repeat
+	pub fn press(&mut self, keycode: Keycode, action: Action) -> bool {
+		if self.keys.insert(keycode, action).is_some() {
+			return false;
+		}
+		self.keys.values().filter(|held| **held == action).count() == 1
+	}
+
+	// return the action if keycode was its last held key
+	pub fn release(&mut self, keycode: Keycode) -> Option<Action> {
+		let action = self.keys.remove(&keycode)?;
+		if self.keys.values().any(|held| *held == action) {
+			None
+		} else {
+			Some(action)
+		}
+	}
+
+	// match keys actually held, e.g. after keys were taken by menu,
+	// return actions pressed(true) or released(false) by the change
+	pub fn sync(&mut self, pressed: &[(Keycode, Action)]) -> Vec<(Action, bool)> {
+		let mut changes = Vec::new();
+		let released: Vec<Keycode> = self
+			.keys
+			.keys()
+			.filter(|keycode| !pressed.iter().any(|(held, _)| held == *keycode))
+			.copied()
+			.collect();
+		for keycode in released {
+			if let Some(action) = self.release(keycode) {
+				changes.push((action, false));
+			}
+		}
+		for (keycode, action) in pressed.iter() {
+			if self.press(*keycode, *action) {
+				changes.push((*action, true));
+			}
+		}
+		changes
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_held_keys() {
+		let mut held_keys = HeldKeys::new();
+		assert!(held_keys.press(Keycode::Z, Action::Fire));
+		// repeat
+		assert!(!held_keys.press(Keycode::Z, Action::Fire));
+		assert!(!held_keys.press(Keycode::X, Action::Fire));
+		assert_eq!(held_keys.release(Keycode::Z), None);
+		assert_eq!(held_keys.release(Keycode::X), Some(Action::Fire));
+		// never pressed
+		assert_eq!(held_keys.release(Keycode::X), None);
+
+		held_keys.press(Keycode::Left, Action::Left);
+		held_keys.press(Keycode::Z, Action::Fire);
+		let changes = held_keys.sync(&[(Keycode::Z, Action::Fire), (Keycode::Up, Action::Up)]);
+		assert_eq!(changes, vec![(Action::Left, false), (Action::Up, true)]);
+		assert!(held_keys
+			.sync(&[(Keycode::Z, Action::Fire), (Keycode::Up, Action::Up)])
+			.is_empty());
+	}
+}
@@ -3,39 +3,84 @@ extern crate rand;
 extern crate rand_pcg;
 extern crate sdl2;
 
+mod agent;
+mod agent_stdio;
+mod audio;
 mod background;
+mod bot;
 mod bullet;
 mod bullet_pool;
+mod calibration;
 mod cannon;
+mod clip;
 mod collision;
+mod debug_overlay;
 mod destroy_effect;
 mod difficulty_manager;
+mod display;
 mod enemy;
 mod enemy_path;
 mod enemy_pool;
+mod export;
 mod file_manager;
 mod fps_indicator;
+mod game_rules;
+mod heatmap;
+mod key_binding;
 mod key_state;
+mod menu;
+mod palette;
 mod player;
+mod png_writer;
 mod random_tools;
 mod record;
+mod retro;
+mod sandbox;
+mod screenshot;
 mod session;
+mod settings;
+mod side_panel;
 mod slowdown_manager;
+mod sound_preview;
+mod stats;
 mod status_bar;
+mod text;
 mod time_manager;
+mod timeline;
+mod wav_writer;
 mod wave_generator;
+mod wave_preview;
 mod window_rect;
 
 use mray::algebra;
 use mray::canvas;
 use mray::graphic_object;
 
+use algebra::Point2f;
+use audio::Audio;
+use cannon::CannonType;
+use difficulty_manager::DifficultyCurve;
+use display::Display;
+use file_manager::FileManager;
+use game_rules::GameRules;
+use key_binding::{Action, HeldKeys, KeyBindings};
+use menu::{Menu, MenuAction, MenuKey};
+use palette::Theme;
+use record::{Params, Practice, Record};
+use sandbox::Sandbox;
 use session::Session;
-use window_rect::WINDOW_SIZE_SCALED;
+use settings::Settings;
+use wave_generator::WAVE_SCHEME_COUNT;
+use window_rect::{fit_scaler, frame_size, parse_point, scaled_size};
 
-use sdl2::event::Event;
+use clap::{App, Arg, ArgMatches};
+use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
 use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Texture, TextureCreator};
+use sdl2::video::{FullscreenType, WindowContext};
 use std::time::SystemTime;
 
 fn find_sdl_gl_driver() -> Option<u32> {
@@ -47,22 +92,340 @@ fn find_sdl_gl_driver() -> Option<u32> {
 	None
 }
 
+fn create_texture(
+	texture_creator: &TextureCreator<WindowContext>,
+	size: (u32, u32),
+) -> Texture<'_> {
+	texture_creator
+		.create_texture_static(Some(sdl2::pixels::PixelFormatEnum::RGB24), size.0, size.1)
+		.unwrap()
+}
+
+// frame is centered and letterboxed in window
+fn fit_viewport(output_size: (u32, u32), panel: bool) -> (f32, Rect) {
+	let scaler = fit_scaler(output_size, frame_size(panel));
+	let size = scaled_size(scaler, frame_size(panel));
+	let viewport = Rect::new(
+		(output_size.0 as i32 - size.0 as i32) / 2,
+		(output_size.1 as i32 - size.1 as i32) / 2,
+		size.0,
+		size.1,
+	);
+	(scaler, viewport)
+}
+
+fn app() -> App<'static, 'static> {
+	App::new("eyhv: Shoot 'em up game inspired by PARSEC47")
+		.arg(
+			Arg::with_name("seed")
+				.short("s")
+				.long("seed")
+				.takes_value(true)
+				.help("random seed used"),
+		)
+		.arg(
+			Arg::with_name("start difficulty")
+				.short("d")
+				.long("start-difficulty")
+				.takes_value(true)
+				.help("difficulty at start"),
+		)
+		.arg(
+			Arg::with_name("difficulty growth")
+				.short("g")
+				.long("difficulty-growth")
+				.takes_value(true)
+				.help("difficulty growth per second, for linear curve"),
+		)
+		.arg(
+			Arg::with_name("difficulty curve")
+				.short("c")
+				.long("difficulty-curve")
+				.takes_value(true)
+				.help("difficulty curve, e.g. \"linear 0.0005\", \"log 0.002 60\", \"stepped 0.01 20\", \"rubber 0.001 3\""),
+		)
+		.arg(
+			Arg::with_name("difficulty preset")
+				.short("p")
+				.long("difficulty-preset")
+				.takes_value(true)
+				.help("difficulty preset(casual, standard, marathon), overridden by other difficulty args"),
+		)
+		.arg(
+			Arg::with_name("difficulty drop")
+				.short("D")
+				.long("difficulty-drop")
+				.takes_value(true)
+				.help("difficulty drop on each hit"),
+		)
+		.arg(
+			Arg::with_name("replay file")
+				.short("f")
+				.long("replay-file")
+				.takes_value(true)
+				.help("enable replay mode and load replay file(other args will be suppressed)"),
+		)
+		.arg(
+			Arg::with_name("rules")
+				.short("r")
+				.long("rules")
+				.takes_value(true)
+				.help("game rules preset(standard, relaxed, hardcore) or rules file"),
+		)
+		.arg(
+			Arg::with_name("practice")
+				.long("practice")
+				.help("start practice directly, difficulty is pinned at start difficulty"),
+		)
+		.arg(
+			Arg::with_name("invincible")
+				.long("invincible")
+				.help("hits are counted but take no effect in practice"),
+		)
+		.arg(
+			Arg::with_name("waves")
+				.long("waves")
+				.takes_value(true)
+				.help("wave scheme ids allowed in practice, e.g. \"2,3\""),
+		)
+		.arg(
+			Arg::with_name("cannons")
+				.long("cannons")
+				.takes_value(true)
+				.help("cannon types allowed in practice, e.g. \"shotgun,rotor\""),
+		)
+		.arg(
+			Arg::with_name("sandbox")
+				.long("sandbox")
+				.takes_value(true)
+				.help("view a cannon type on a stationary enemy, with seed and start difficulty"),
+		)
+		.arg(
+			Arg::with_name("at")
+				.long("at")
+				.takes_value(true)
+				.help("sandbox enemy position, e.g. \"250,200\""),
+		)
+		.arg(
+			Arg::with_name("theme")
+				.long("theme")
+				.takes_value(true)
+				.help("color theme preset(classic, colorblind, high_contrast) or theme file, overrides settings"),
+		)
+		.arg(
+			Arg::with_name("agent stdio")
+				.long("agent-stdio")
+				.help("run headless, driven by json lines on stdin, see README"),
+		)
+		.arg(
+			Arg::with_name("agent dt")
+				.long("agent-dt")
+				.takes_value(true)
+				.help("fixed dt of each agent step, default 1/60"),
+		)
+		.subcommand(wave_preview::subcommand())
+		.subcommand(screenshot::subcommand())
+		.subcommand(export::subcommand())
+		.subcommand(sound_preview::subcommand())
+		.subcommand(bot::subcommand())
+		.subcommand(calibration::subcommand())
+		.subcommand(heatmap::subcommand())
+}
+
+// preset name, params without seed, rules name and rules
+fn parse_params(matches: &ArgMatches) -> (String, Params, String, GameRules) {
+	let mut preset_name = matches
+		.value_of("difficulty preset")
+		.unwrap_or("standard")
+		.to_string();
+	let (start_difficulty, curve, difficulty_drop) = difficulty_manager::preset(&preset_name)
+		.unwrap_or_else(|| panic!("Unknown difficulty preset {}", preset_name));
+	let mut params = Params {
+		seed: 0,
+		start_difficulty,
+		curve,
+		difficulty_drop,
+	};
+	if let Some(start_difficulty) = matches.value_of("start difficulty") {
+		params.start_difficulty = start_difficulty.parse::<f32>().unwrap();
+		preset_name = "custom".to_string();
+	}
+	if let Some(difficulty_growth) = matches.value_of("difficulty growth") {
+		params.curve = DifficultyCurve::Linear {
+			growth: difficulty_growth.parse::<f32>().unwrap(),
+		};
+		preset_name = "custom".to_string();
+	}
+	if let Some(curve) = matches.value_of("difficulty curve") {
+		params.curve = DifficultyCurve::from_str(curve)
+			.unwrap_or_else(|e| panic!("Failed to parse difficulty curve: {}", e));
+		preset_name = "custom".to_string();
+	}
+	if let Some(difficulty_drop) = matches.value_of("difficulty drop") {
+		params.difficulty_drop = difficulty_drop.parse::<f32>().unwrap();
+		preset_name = "custom".to_string();
+	}
+	let (rules_name, rules) = match matches.value_of("rules") {
+		None => ("standard".to_string(), Default::default()),
+		Some(arg) => match GameRules::from_arg(arg) {
+			Ok(rules) => {
+				if GameRules::preset(arg).is_some() {
+					(arg.to_string(), rules)
+				} else {
+					("custom".to_string(), rules)
+				}
+			}
+			Err(e) => panic!("Failed to load rules {}: {}", arg, e),
+		},
+	};
+	(preset_name, params, rules_name, rules)
+}
+
+fn parse_args(matches: &ArgMatches) -> (Menu, Option<Session>, Option<Sandbox>) {
+	// command line args are used as initial values of menu
+	let seed = matches
+		.value_of("seed")
+		.map(|seed| seed.parse::<u64>().unwrap());
+	let (preset_name, params, rules_name, rules) = parse_params(matches);
+	let practice = Practice {
+		invincible: matches.is_present("invincible"),
+		waves: matches.value_of("waves").map(|waves| {
+			waves
+				.split(',')
+				.map(|id| {
+					let id = id.parse::<u32>().unwrap();
+					if id >= WAVE_SCHEME_COUNT {
+						panic!("Wave scheme id should be less than {}", WAVE_SCHEME_COUNT);
+					}
+					id
+				})
+				.collect()
+		}),
+		cannon_types: matches.value_of("cannons").map(|cannons| {
+			cannons
+				.split(',')
+				.map(|name| {
+					CannonType::from_name(name)
+						.unwrap_or_else(|| panic!("Unknown cannon type {}", name))
+				})
+				.collect()
+		}),
+	};
+	let sandbox = matches.value_of("sandbox").map(|name| {
+		let cannon_type =
+			CannonType::from_name(name).unwrap_or_else(|| panic!("Unknown cannon type {}", name));
+		let enemy_p = match matches.value_of("at") {
+			None => Point2f::from_floats(250., 200.),
+			Some(at) => parse_point(at).unwrap_or_else(|e| panic!("Invalid --at: {}", e)),
+		};
+		Sandbox::new(
+			cannon_type,
+			seed.unwrap_or(0),
+			params.start_difficulty,
+			enemy_p,
+		)
+	});
+	let mut menu = Menu::new(
+		seed,
+		params,
+		preset_name,
+		rules_name,
+		rules.clone(),
+		practice.clone(),
+	);
+	let session = match matches.value_of("replay file") {
+		Some(replay_file) => match load_replay(replay_file.to_string()) {
+			Ok(session) => Some(session),
+			Err(e) => {
+				menu.show_error(format!("Failed to load replay {}: {}", replay_file, e));
+				None
+			}
+		},
+		None if matches.is_present("practice") => {
+			Some(Session::new(menu.params(), rules, Some(practice)))
+		}
+		None => None,
+	};
+	(menu, session, sandbox)
+}
+
+fn load_replay(replay_file: String) -> Result<Session, String> {
+	Ok(Session::from_record(Record::load(replay_file)?))
+}
+
+// bound actions are used for navigation, with fixed fallback keys
+fn menu_key(keycode: Keycode, action: Option<Action>) -> Option<MenuKey> {
+	match (action, keycode) {
+		(Some(Action::Up), _) | (_, Keycode::Up) => Some(MenuKey::Up),
+		(Some(Action::Down), _) | (_, Keycode::Down) => Some(MenuKey::Down),
+		(Some(Action::Left), _) | (_, Keycode::Left) => Some(MenuKey::Left),
+		(Some(Action::Right), _) | (_, Keycode::Right) => Some(MenuKey::Right),
+		(Some(Action::Fire), _) | (_, Keycode::Return) => Some(MenuKey::Confirm),
+		(Some(Action::Pause), _) | (_, Keycode::Escape) => Some(MenuKey::Back),
+		(_, Keycode::Backspace) => Some(MenuKey::Erase),
+		_ => {
+			let name = keycode.name();
+			let mut chars = name.chars();
+			match (chars.next(), chars.next()) {
+				(Some(ch), None) if ch.is_ascii_digit() => Some(MenuKey::Char(ch)),
+				_ => None,
+			}
+		}
+	}
+}
+
 pub fn main() {
-	let mut session = Session::new();
+	let matches = app().get_matches();
+	// stdout is the agent protocol, so nothing else may print before it
+	if matches.is_present("agent stdio") {
+		let (_, mut params, _, rules) = parse_params(&matches);
+		params.seed = matches
+			.value_of("seed")
+			.map(|seed| seed.parse::<u64>().unwrap())
+			.unwrap_or(0);
+		let dt = matches
+			.value_of("agent dt")
+			.map(|dt| dt.parse::<f32>().unwrap())
+			.unwrap_or(1. / 60.);
+		return agent_stdio::run(params, rules, dt);
+	}
+	let file_manager = FileManager::new();
+	let mut settings = Settings::load(&file_manager);
+	// graphics are built with theme colors, so theme is set before anything else
+	let theme_arg = matches.value_of("theme").unwrap_or(&settings.theme);
+	match Theme::from_arg(theme_arg) {
+		Ok(theme) => palette::set_theme(theme),
+		Err(e) => println!("Failed to load theme {}: {}", theme_arg, e),
+	}
+	// headless tools
+	match matches.subcommand() {
+		("preview", Some(matches)) => return wave_preview::run(matches),
+		("screenshot", Some(matches)) => return screenshot::run(matches),
+		("export", Some(matches)) => return export::run(matches),
+		("sounds", Some(matches)) => return sound_preview::run(matches),
+		("bot", Some(matches)) => return bot::run(matches),
+		("calibrate", Some(matches)) => return calibration::run(matches),
+		("heatmap", Some(matches)) => return heatmap::run(matches),
+		_ => {}
+	}
+	let (mut menu, mut session, mut sandbox) = parse_args(&matches);
+	let key_bindings = KeyBindings::load(&file_manager);
+	let mut held_keys = HeldKeys::new();
 
 	let sdl_context = sdl2::init().unwrap();
 	let video_subsystem = sdl_context.video().unwrap();
+	let mut audio = Audio::new(&sdl_context, settings.volume);
 
-	let window = video_subsystem
-		.window(
-			"eyhv",
-			WINDOW_SIZE_SCALED.x as u32,
-			WINDOW_SIZE_SCALED.y as u32,
-		)
+	let mut window = video_subsystem
+		.window("eyhv", settings.window_width, settings.window_height)
 		.opengl()
+		.resizable()
 		.position_centered()
 		.build()
 		.unwrap();
+	if settings.fullscreen {
+		window.set_fullscreen(FullscreenType::Desktop).unwrap();
+	}
 
 	let mut canvas = window
 		.into_canvas()
@@ -71,58 +434,233 @@ pub fn main() {
 		.unwrap();
 	canvas.present();
 	let texture_creator = canvas.texture_creator();
-	let mut texture = texture_creator
-		.create_texture_static(
-			Some(sdl2::pixels::PixelFormatEnum::RGB24),
-			WINDOW_SIZE_SCALED.x as u32,
-			WINDOW_SIZE_SCALED.y as u32,
-		)
-		.unwrap();
+	let (mut scaler, mut viewport) =
+		fit_viewport(canvas.output_size().unwrap(), settings.side_panel);
+	// all scenes are drawn through display
+	let mut display = Display::new(scaler, settings.retro, settings.side_panel);
+	let mut texture = create_texture(&texture_creator, display.get_size());
 	let mut event_pump = sdl_context.event_pump().unwrap();
 
 	let mut last_time = SystemTime::now();
 	'running: loop {
+		let mut take_screenshot = false;
+		let mut resized = false;
+		// last clicked or dragged point of this frame, and whether it was released
+		let mut scrub = None;
+		let mut scrub_released = false;
+		let mut resumed = false;
+		let frame_p = |x: i32, y: i32| {
+			Point2f::from_floats((x - viewport.x()) as f32, (y - viewport.y()) as f32) / scaler
+		};
 		for event in event_pump.poll_iter() {
-			match event {
-				Event::Quit { .. }
-				| Event::KeyDown {
-					keycode: Some(Keycode::Q),
-					..
-				} => {
-					session.exit();
-					break 'running;
-				}
+			let menu_action = match event {
+				Event::Quit { .. } => Some(MenuAction::Quit),
 				Event::KeyDown {
 					keycode: Some(keycode),
 					..
-				} => match keycode {
-					Keycode::Left => session.proc_key(0, true),
-					Keycode::Up => session.proc_key(1, true),
-					Keycode::Right => session.proc_key(2, true),
-					Keycode::Down => session.proc_key(3, true),
-					Keycode::LShift => session.proc_key(4, true),
-					Keycode::Z => session.proc_key(5, true),
-					Keycode::LAlt => session.proc_key(6, true),
-					Keycode::Space => session.proc_key(7, true),
-					Keycode::F => session.proc_key(8, true),
-					_ => {}
-				},
+				} => {
+					let action = key_bindings.get(keycode);
+					match session.as_mut() {
+						_ if action == Some(Action::Quit) => Some(MenuAction::Quit),
+						_ if action == Some(Action::Screenshot) => {
+							take_screenshot = true;
+							None
+						}
+						_ if action == Some(Action::Fullscreen) => {
+							settings.fullscreen = !settings.fullscreen;
+							canvas
+								.window_mut()
+								.set_fullscreen(if settings.fullscreen {
+									FullscreenType::Desktop
+								} else {
+									FullscreenType::Off
+								})
+								.unwrap();
+							settings.save(&file_manager);
+							None
+						}
+						_ if action == Some(Action::RetroMode) => {
+							settings.retro = !settings.retro;
+							display.set_retro(settings.retro);
+							settings.save(&file_manager);
+							None
+						}
+						_ if action == Some(Action::SidePanel) => {
+							settings.side_panel = !settings.side_panel;
+							display.set_panel(settings.side_panel);
+							settings.save(&file_manager);
+							// frame size is changed
+							resized = true;
+							None
+						}
+						// sandbox uses menu keys, back to title on escape
+						_ if sandbox.is_some() => {
+							if let Some(key) = menu_key(keycode, action) {
+								if !sandbox.as_mut().unwrap().proc_key(key) {
+									sandbox = None;
+									menu.open_title();
+								}
+							}
+							None
+						}
+						// menu takes keys when there is no running game
+						None => menu_key(keycode, action).and_then(|key| menu.proc_key(key)),
+						Some(session) if session.is_paused() => {
+							menu_key(keycode, action).and_then(|key| menu.proc_key(key))
+						}
+						Some(session) => {
+							// only keys sent to session are held, so that releases match,
+							// repeats and other keys of a held action are not sent
+							if let Some(action) = action {
+								if let (true, Some(key_id)) =
+									(held_keys.press(keycode, action), action.key_id())
+								{
+									session.proc_key(key_id, true);
+								}
+							}
+							if session.is_paused() {
+								menu.open_pause();
+							}
+							None
+						}
+					}
+				}
 				Event::KeyUp {
 					keycode: Some(keycode),
 					..
-				} => match keycode {
-					Keycode::Left => session.proc_key(0, false),
-					Keycode::Up => session.proc_key(1, false),
-					Keycode::Right => session.proc_key(2, false),
-					Keycode::Down => session.proc_key(3, false),
-					Keycode::LShift => session.proc_key(4, false),
-					Keycode::Z => session.proc_key(5, false),
-					Keycode::Space => session.proc_key(7, false),
-					_ => (),
+				} => {
+					if let (Some(session), Some(action)) =
+						(session.as_mut(), held_keys.release(keycode))
+					{
+						if let (true, Some(key_id)) = (action.has_release(), action.key_id()) {
+							session.proc_key(key_id, false);
+						}
+					}
+					None
+				}
+				Event::Window {
+					win_event: WindowEvent::SizeChanged(..),
+					..
+				} => {
+					resized = true;
+					None
+				}
+				// left click moves sandbox enemy, right click moves target,
+				// left drag on replay timeline seeks on release
+				Event::MouseButtonDown {
+					mouse_btn, x, y, ..
+				} => {
+					let p = frame_p(x, y);
+					if let Some(sandbox) = sandbox.as_mut() {
+						match mouse_btn {
+							MouseButton::Left => sandbox.set_enemy_p(p),
+							MouseButton::Right => sandbox.set_target_p(p),
+							_ => {}
+						}
+					} else if mouse_btn == MouseButton::Left {
+						scrub = Some(p);
+					}
+					None
+				}
+				Event::MouseMotion {
+					mousestate, x, y, ..
+				} if mousestate.left() => {
+					scrub = Some(frame_p(x, y));
+					None
+				}
+				Event::MouseButtonUp {
+					mouse_btn: MouseButton::Left,
+					..
+				} => {
+					scrub_released = true;
+					None
+				}
+				_ => None,
+			};
+			match menu_action {
+				None => {}
+				Some(MenuAction::Play(params, rules)) => {
+					session = Some(Session::new(params, rules, None));
+				}
+				Some(MenuAction::Practice(params, rules, practice)) => {
+					session = Some(Session::new(params, rules, Some(practice)));
+				}
+				Some(MenuAction::Replay(replay_file)) => match load_replay(replay_file.clone()) {
+					Ok(replay) => session = Some(replay),
+					Err(e) => {
+						menu.show_error(format!("Failed to load replay {}: {}", replay_file, e))
+					}
 				},
-				_ => {}
+				Some(MenuAction::Sandbox(seed, difficulty)) => {
+					sandbox = Some(Sandbox::new(
+						CannonType::PlayerLocker,
+						seed,
+						difficulty,
+						Point2f::from_floats(250., 200.),
+					));
+				}
+				Some(MenuAction::Resume) => {
+					if let Some(session) = session.as_mut() {
+						session.set_pause(false);
+						resumed = true;
+					}
+				}
+				Some(MenuAction::ExitToTitle) => match session.take() {
+					Some(session) => {
+						session.exit();
+						menu.open_results(session.panel_info(), session.get_timeline().clone());
+					}
+					None => menu.open_title(),
+				},
+				Some(MenuAction::Quit) => {
+					if let Some(session) = session.as_ref() {
+						session.exit();
+					}
+					break 'running;
+				}
+			}
+		}
+		// keys pressed or released in pause menu are sent on resume
+		if let (true, Some(session)) = (resumed, session.as_mut()) {
+			let pressed: Vec<(Keycode, Action)> = event_pump
+				.keyboard_state()
+				.pressed_scancodes()
+				.filter_map(Keycode::from_scancode)
+				.filter_map(|keycode| key_bindings.get(keycode).map(|action| (keycode, action)))
+				.collect();
+			for (action, updown) in held_keys.sync(&pressed) {
+				if let (true, Some(key_id)) = (action.has_release(), action.key_id()) {
+					session.proc_key(key_id, updown);
+				}
+			}
+		}
+		if let Some(session) = session.as_mut() {
+			if let Some(p) = scrub {
+				session.drag_seek(p);
+			}
+			if scrub_released {
+				session.release_seek();
 			}
 		}
+		if resized {
+			let output_size = canvas.output_size().unwrap();
+			let (new_scaler, new_viewport) = fit_viewport(output_size, display.has_panel());
+			viewport = new_viewport;
+			if new_scaler != scaler {
+				scaler = new_scaler;
+				display.set_scaler(scaler);
+			}
+			let texture_query = texture.query();
+			if (texture_query.width, texture_query.height) != display.get_size() {
+				texture = create_texture(&texture_creator, display.get_size());
+			}
+			// saved on quit, resizing sends many events
+			if !settings.fullscreen {
+				settings.window_width = output_size.0;
+				settings.window_height = output_size.1;
+			}
+		}
+
 		// The rest of the game loop goes here...
 		let current_time = SystemTime::now();
 		let duration_secs = current_time
@@ -130,22 +668,50 @@ pub fn main() {
 			.expect("Time error")
 			.as_secs_f32();
 		last_time = current_time;
-		if !session.tick(duration_secs) {
-			break 'running;
+		display.begin_frame(duration_secs);
+		match session.as_mut() {
+			_ if sandbox.is_some() => {
+				let sandbox = sandbox.as_mut().unwrap();
+				sandbox.tick(duration_secs);
+				sandbox.render(&mut display);
+				std::thread::sleep(std::time::Duration::new(0, 1_000_000_000u32 / 120));
+			}
+			None => {
+				menu.tick(duration_secs);
+				menu.render(&mut display);
+				// title screen has no frame limiter in tick
+				std::thread::sleep(std::time::Duration::new(0, 1_000_000_000u32 / 120));
+			}
+			Some(running_session) => {
+				if !running_session.tick(duration_secs) {
+					// replay finished
+					menu.open_results(
+						running_session.panel_info(),
+						running_session.get_timeline().clone(),
+					);
+					session = None;
+					continue 'running;
+				}
+				audio.play(running_session.sound_events());
+				running_session.render(&mut display);
+				if running_session.is_paused() {
+					display.draw(menu.graphic_objects_iter());
+				}
+			}
+		}
+
+		let size = display.get_size();
+		let data = display.get_data();
+		if take_screenshot {
+			screenshot::save_canvas(&file_manager.get_screenshot_path(), size, data);
 		}
-		session.render();
 
-		texture
-			.update(
-				None,
-				&session.canvas.data,
-				WINDOW_SIZE_SCALED.x as usize * 3,
-			)
-			.unwrap();
+		texture.update(None, data, size.0 as usize * 3).unwrap();
 
 		canvas.set_draw_color(Color::RGBA(0, 0, 0, 255));
 		canvas.clear();
-		canvas.copy(&texture, None, None).unwrap();
+		canvas.copy(&texture, None, Some(viewport)).unwrap();
 		canvas.present();
 	}
+	settings.save(&file_manager);
 }
@@ -0,0 +1,529 @@
+use crate::algebra::{Point2f, Rect2f};
+use crate::background::Background;
+use crate::cannon::CANNON_TYPES;
+use crate::difficulty_manager::{self, DifficultyCurve, CURVE_NAMES, DIFFICULTY_MULTIPLIER};
+use crate::display::Display;
+use crate::file_manager::FileManager;
+use crate::game_rules::{self, GameRules};
+use crate::graphic_object::{GraphicObjects, GraphicObjectsIntoIter, Polygon2f};
+use crate::palette;
+use crate::record::{Params, Practice};
+use crate::side_panel::PanelInfo;
+use crate::text::centered_text_graphic_objects;
+use crate::timeline::Timeline;
+use crate::wave_generator::WAVE_SCHEME_COUNT;
+use crate::window_rect::WINDOW_SIZE;
+
+// keys are mapped from keycodes in main, independent of game key ids
+pub enum MenuKey {
+	Up,
+	Down,
+	Left,
+	Right,
+	Confirm,
+	Back,
+	Char(char),
+	Erase,
+}
+
+pub enum MenuAction {
+	Play(Params, GameRules),
+	Practice(Params, GameRules, Practice),
+	Replay(String),
+	// seed, difficulty
+	Sandbox(u64, f32),
+	Resume,
+	ExitToTitle,
+	Quit,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Page {
+	Title,
+	Settings,
+	Practice,
+	Replays,
+	Pause,
+	Results,
+}
+
+pub struct Menu {
+	page: Page,
+	cursor: usize,
+
+	// empty seed means generating one when game starts
+	seed: String,
+	// params.seed is not used
+	params: Params,
+	// edited params are named "custom"
+	preset_name: String,
+	// rules loaded from file are named "custom"
+	rules_name: String,
+	rules: GameRules,
+	practice: Practice,
+	// pinned level of practice, separate from start difficulty of play
+	practice_difficulty: f32,
+
+	replays: Vec<String>,
+	file_manager: FileManager,
+	// of last finished session
+	results: Option<(PanelInfo, Timeline)>,
+	// shown until next key
+	error: Option<String>,
+
+	background: Background,
+}
+
+impl Menu {
+	pub fn new(
+		seed: Option<u64>,
+		params: Params,
+		preset_name: String,
+		rules_name: String,
+		rules: GameRules,
+		practice: Practice,
+	) -> Menu {
+		Menu {
+			page: Page::Title,
+			cursor: 0,
+			seed: seed.map(|seed| seed.to_string()).unwrap_or_default(),
+			practice_difficulty: params.start_difficulty,
+			params,
+			preset_name,
+			rules_name,
+			rules,
+			practice,
+			replays: Vec::new(),
+			file_manager: FileManager::new(),
+			results: None,
+			error: None,
+			background: Background::new(),
+		}
+	}
+
+	pub fn open_title(&mut self) {
+		self.page = Page::Title;
+		self.cursor = 0;
+	}
+
+	pub fn open_pause(&mut self) {
+		self.page = Page::Pause;
+		self.cursor = 0;
+	}
+
+	pub fn open_results(&mut self, info: PanelInfo, timeline: Timeline) {
+		self.results = Some((info, timeline));
+		self.page = Page::Results;
+		self.cursor = 0;
+	}
+
+	pub fn show_error(&mut self, error: String) {
+		println!("{}", error);
+		self.error = Some(error);
+	}
+
+	fn open_replays(&mut self) {
+		self.replays = self.file_manager.list_replays();
+		self.page = Page::Replays;
+		self.cursor = 0;
+	}
+
+	fn items(&self) -> Vec<String> {
+		match self.page {
+			Page::Title => vec!["PLAY", "PRACTICE", "REPLAYS", "SETTINGS", "SANDBOX", "QUIT"]
+				.into_iter()
+				.map(|x| x.to_string())
+				.collect(),
+			Page::Settings => vec![
+				format!(
+					"SEED {: >12}",
+					if self.seed.is_empty() {
+						"RANDOM"
+					} else {
+						&self.seed
+					}
+				),
+				format!("PRESET {: >10}", self.preset_name.to_uppercase()),
+				format!("START {: >11.3}", self.params.start_difficulty),
+				format!("CURVE {: >11}", self.params.curve.name().to_uppercase()),
+				format!("GROWTH {: >10.5}", self.params.curve.clone().growth_mut()),
+				format!("DROP {: >12.4}", self.params.difficulty_drop),
+				format!("RULES {: >11}", self.rules_name.to_uppercase()),
+				"BACK".to_string(),
+			],
+			Page::Practice => vec![
+				format!(
+					"INVINCIBLE {: >6}",
+					if self.practice.invincible {
+						"ON"
+					} else {
+						"OFF"
+					}
+				),
+				format!(
+					"WAVES {: >11}",
+					match &self.practice.waves {
+						None => "ALL".to_string(),
+						Some(waves) => waves
+							.iter()
+							.map(|id| id.to_string())
+							.collect::<Vec<String>>()
+							.join(","),
+					}
+				),
+				format!(
+					"CANNONS {: >9}",
+					match &self.practice.cannon_types {
+						None => "ALL".to_string(),
+						Some(cannon_types) => cannon_types
+							.iter()
+							.map(|cannon_type| cannon_type.name().to_uppercase())
+							.collect::<Vec<String>>()
+							.join(","),
+					}
+				),
+				format!("LEVEL {: >11.3}", self.practice_difficulty),
+				"START".to_string(),
+				"BACK".to_string(),
+			],
+			Page::Replays => {
+				let mut items: Vec<String> = self
+					.replays
+					.iter()
+					.map(|path| {
+						std::path::Path::new(path)
+							.file_name()
+							.unwrap()
+							.to_string_lossy()
+							.to_uppercase()
+					})
+					.collect();
+				items.push("BACK".to_string());
+				items
+			}
+			Page::Pause => vec!["RESUME", "EXIT TO TITLE", "QUIT"]
+				.into_iter()
+				.map(|x| x.to_string())
+				.collect(),
+			Page::Results => vec!["BACK".to_string()],
+		}
+	}
+
+	pub fn params(&self) -> Params {
+		let seed = match self.seed.parse::<u64>() {
+			Ok(seed) => seed,
+			Err(_) => {
+				use rand::Rng;
+				use rand::SeedableRng;
+				let mut rng = rand_pcg::Pcg64Mcg::from_entropy();
+				let seed = rng.gen::<u64>();
+				println!("Seed generated: {}", seed);
+				seed
+			}
+		};
+		Params {
+			seed,
+			..self.params.clone()
+		}
+	}
+
+	fn edit(&mut self, step: f32) {
+		// names not in list(e.g. "custom") cycle from the first one
+		fn cycle(names: &[&'static str], current: &str, step: f32) -> &'static str {
+			let len = names.len() as i32;
+			match names.iter().position(|name| *name == current) {
+				Some(id) => names[((id as i32 + step as i32 + len) % len) as usize],
+				None => names[0],
+			}
+		}
+		match self.cursor {
+			1 => {
+				self.preset_name =
+					cycle(&difficulty_manager::PRESETS, &self.preset_name, step).to_string();
+				let (start_difficulty, curve, difficulty_drop) =
+					difficulty_manager::preset(&self.preset_name).unwrap();
+				self.params.start_difficulty = start_difficulty;
+				self.params.curve = curve;
+				self.params.difficulty_drop = difficulty_drop;
+			}
+			2 => {
+				self.params.start_difficulty =
+					(self.params.start_difficulty + step * 0.01).clamp(0., 1.);
+				self.preset_name = "custom".to_string();
+			}
+			3 => {
+				let name = cycle(&CURVE_NAMES, self.params.curve.name(), step);
+				self.params.curve = DifficultyCurve::from_name(name).unwrap();
+				self.preset_name = "custom".to_string();
+			}
+			4 => {
+				// step size is a fraction of default value of the curve
+				let unit = *DifficultyCurve::from_name(self.params.curve.name())
+					.unwrap()
+					.growth_mut() / 5.;
+				let growth = self.params.curve.growth_mut();
+				*growth = (*growth + step * unit).max(0.);
+				self.preset_name = "custom".to_string();
+			}
+			5 => {
+				self.params.difficulty_drop = (self.params.difficulty_drop + step * 0.001).max(0.);
+				self.preset_name = "custom".to_string();
+			}
+			6 => {
+				self.rules_name = cycle(&game_rules::PRESETS, &self.rules_name, step).to_string();
+				self.rules = GameRules::preset(&self.rules_name).unwrap();
+			}
+			_ => {}
+		}
+	}
+
+	fn edit_practice(&mut self, step: f32) {
+		// cycle through ALL and each single choice
+		fn cycle(current: Option<usize>, len: usize, step: f32) -> Option<usize> {
+			let len = len as i32 + 1;
+			let id = current.map(|id| id as i32 + 1).unwrap_or(0);
+			match (id + step as i32 + len) % len {
+				0 => None,
+				id => Some(id as usize - 1),
+			}
+		}
+		match self.cursor {
+			0 => self.practice.invincible = !self.practice.invincible,
+			1 => {
+				let current = self.practice.waves.as_ref().map(|waves| waves[0] as usize);
+				self.practice.waves =
+					cycle(current, WAVE_SCHEME_COUNT as usize, step).map(|id| vec![id as u32]);
+			}
+			2 => {
+				let current = self.practice.cannon_types.as_ref().map(|cannon_types| {
+					CANNON_TYPES
+						.iter()
+						.position(|cannon_type| *cannon_type == cannon_types[0])
+						.unwrap()
+				});
+				self.practice.cannon_types =
+					cycle(current, CANNON_TYPES.len(), step).map(|id| vec![CANNON_TYPES[id]]);
+			}
+			3 => {
+				self.practice_difficulty = (self.practice_difficulty + step * 0.01).clamp(0., 1.);
+			}
+			_ => {}
+		}
+	}
+
+	fn confirm(&mut self) -> Option<MenuAction> {
+		match (self.page, self.cursor) {
+			(Page::Title, 0) => Some(MenuAction::Play(self.params(), self.rules.clone())),
+			(Page::Title, 1) => {
+				self.page = Page::Practice;
+				self.cursor = 0;
+				None
+			}
+			(Page::Title, 2) => {
+				self.open_replays();
+				None
+			}
+			(Page::Title, 3) => {
+				self.page = Page::Settings;
+				self.cursor = 0;
+				None
+			}
+			(Page::Title, 4) => Some(MenuAction::Sandbox(
+				self.params().seed,
+				self.params.start_difficulty,
+			)),
+			(Page::Title, 5) => Some(MenuAction::Quit),
+			(Page::Settings, 7) => self.back(),
+			(Page::Practice, 4) => Some(MenuAction::Practice(
+				Params {
+					start_difficulty: self.practice_difficulty,
+					..self.params()
+				},
+				self.rules.clone(),
+				self.practice.clone(),
+			)),
+			(Page::Practice, 5) => self.back(),
+			(Page::Replays, cursor) => {
+				if cursor < self.replays.len() {
+					Some(MenuAction::Replay(self.replays[cursor].clone()))
+				} else {
+					self.back()
+				}
+			}
+			(Page::Pause, 0) => Some(MenuAction::Resume),
+			(Page::Pause, 1) => Some(MenuAction::ExitToTitle),
+			(Page::Pause, 2) => Some(MenuAction::Quit),
+			(Page::Results, _) => self.back(),
+			_ => None,
+		}
+	}
+
+	fn back(&mut self) -> Option<MenuAction> {
+		match self.page {
+			Page::Title => None,
+			Page::Settings | Page::Practice | Page::Replays | Page::Results => {
+				self.open_title();
+				None
+			}
+			Page::Pause => Some(MenuAction::Resume),
+		}
+	}
+
+	pub fn proc_key(&mut self, key: MenuKey) -> Option<MenuAction> {
+		self.error = None;
+		let len = self.items().len();
+		match key {
+			MenuKey::Up => {
+				self.cursor = (self.cursor + len - 1) % len;
+				None
+			}
+			MenuKey::Down => {
+				self.cursor = (self.cursor + 1) % len;
+				None
+			}
+			MenuKey::Left => {
+				match self.page {
+					Page::Settings => self.edit(-1.),
+					Page::Practice => self.edit_practice(-1.),
+					_ => {}
+				}
+				None
+			}
+			MenuKey::Right => {
+				match self.page {
+					Page::Settings => self.edit(1.),
+					Page::Practice => self.edit_practice(1.),
+					_ => {}
+				}
+				None
+			}
+			MenuKey::Confirm => self.confirm(),
+			MenuKey::Back => self.back(),
+			MenuKey::Char(ch) => {
+				// u64 has at most 20 digits
+				if self.page == Page::Settings
+					&& self.cursor == 0
+					&& ch.is_ascii_digit()
+					&& self.seed.len() < 20
+				{
+					self.seed.push(ch);
+					if self.seed.parse::<u64>().is_err() {
+						self.seed.pop();
+					}
+				}
+				None
+			}
+			MenuKey::Erase => {
+				if self.page == Page::Settings && self.cursor == 0 {
+					self.seed.pop();
+				}
+				None
+			}
+		}
+	}
+
+	pub fn tick(&mut self, dt: f32) {
+		self.background.tick(dt, false);
+	}
+
+	pub fn graphic_objects_iter(&self) -> GraphicObjectsIntoIter {
+		const ITEM_SIZE: f32 = 20.;
+		const ITEM_SPACE: f32 = 40.;
+		let mut graphic_objects: GraphicObjects = Default::default();
+		let (title, items_y) = match self.page {
+			Page::Title => ("EYHV", 300.),
+			Page::Settings => ("SETTINGS", 250.),
+			Page::Practice => ("PRACTICE", 250.),
+			Page::Replays => ("REPLAYS", 200.),
+			Page::Pause => {
+				graphic_objects.push(Box::new(Polygon2f {
+					vertices: vec![
+						Point2f::from_floats(0., 0.),
+						Point2f::from_floats(WINDOW_SIZE.x, 0.),
+						Point2f::from_floats(WINDOW_SIZE.x, WINDOW_SIZE.y),
+						Point2f::from_floats(0., WINDOW_SIZE.y),
+					],
+					color: [0., 0., 0., 0.6],
+					border_color: [0., 0., 0., 0.6],
+				}));
+				("PAUSE", 300.)
+			}
+			Page::Results => {
+				// difficulty over time shows where the run stalled
+				let (info, timeline) = self.results.as_ref().unwrap();
+				let lines = [
+					format!(
+						"MAX LV {}  HITS {}",
+						match info.max_difficulty {
+							Some(max_difficulty) =>
+								((max_difficulty * DIFFICULTY_MULTIPLIER) as u32).to_string(),
+							None => "-".to_string(),
+						},
+						info.hits
+					),
+					format!("TIME {:.1}  SEED {}", info.time, info.seed),
+				];
+				for (i, line) in lines.iter().enumerate() {
+					graphic_objects.extend(centered_text_graphic_objects(
+						line,
+						16.,
+						WINDOW_SIZE.x,
+						180. + i as f32 * 30.,
+						Some(palette::color_alpha("hud_text", [1., 1., 1., 0.8])),
+					));
+				}
+				graphic_objects.extend(
+					timeline.graphic_objects(&Rect2f::from_floats(30., 260., 470., 480.), None),
+				);
+				("RESULTS", 560.)
+			}
+		};
+		let title_size = if self.page == Page::Title { 60. } else { 30. };
+		// results have the graph between title and items
+		let title_y = if self.page == Page::Results {
+			100.
+		} else {
+			items_y - 120.
+		};
+		graphic_objects.extend(centered_text_graphic_objects(
+			title,
+			title_size,
+			WINDOW_SIZE.x,
+			title_y,
+			Some([0.6, 0.9, 1., 1.]),
+		));
+		for (i, item) in self.items().iter().enumerate() {
+			graphic_objects.extend(centered_text_graphic_objects(
+				item,
+				ITEM_SIZE,
+				WINDOW_SIZE.x,
+				items_y + i as f32 * ITEM_SPACE,
+				Some(if i == self.cursor {
+					palette::color_alpha("hud_highlight", [1., 1., 0.4, 1.])
+				} else {
+					palette::color_alpha("hud_text", [1., 1., 1., 0.5])
+				}),
+			));
+		}
+		// one line per part of "what: why", shrunk to fit
+		if let Some(error) = self.error.as_ref() {
+			for (i, line) in error.to_uppercase().split(": ").enumerate() {
+				let size = 12f32.min((WINDOW_SIZE.x - 20.) / line.chars().count() as f32);
+				graphic_objects.extend(centered_text_graphic_objects(
+					line,
+					size,
+					WINDOW_SIZE.x,
+					630. + i as f32 * 18.,
+					Some(palette::color_alpha("hud_level_drop", [1., 0.4, 0.4, 1.])),
+				));
+			}
+		}
+		graphic_objects.into_iter()
+	}
+
+	// title pages are drawn without session
+	pub fn render(&self, display: &mut Display) {
+		display.draw(self.background.graphic_objects_iter());
+		display.draw(self.graphic_objects_iter());
+	}
+}
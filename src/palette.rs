@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+use crate::graphic_object::GraphicObjects;
+
+// semantic color roles, graphics name a role and keep their own color as default
+pub const ROLES: [&str; 15] = [
+	"player",
+	"player_hit",
+	"player_bullet",
+	"enemy_hull",
+	"enemy_detail",
+	"enemy_bullet",
+	"enemy_bullet_a",
+	"enemy_bullet_b",
+	"laser",
+	"hud_level",
+	"hud_level_drop",
+	"hud_quick",
+	"hud_slow",
+	"hud_text",
+	"hud_highlight",
+];
+
+// rgb of roles, roles not in theme keep default colors
+#[derive(Clone, Debug, Default)]
+pub struct Theme {
+	colors: HashMap<String, [f32; 3]>,
+}
+
+impl Theme {
+	fn from_pairs(pairs: &[(&str, [f32; 3])]) -> Theme {
+		Theme {
+			colors: pairs
+				.iter()
+				.map(|(role, color)| (role.to_string(), *color))
+				.collect(),
+		}
+	}
+
+	pub fn preset(name: &str) -> Option<Theme> {
+		match name {
+			"classic" => Some(Default::default()),
+			// Okabe-Ito colors, distinguishable with common color vision deficiencies
+			"colorblind" => Some(Theme::from_pairs(&[
+				("player", [0.8, 0.9, 1.]),
+				("player_hit", [0.84, 0.37, 0.]),
+				("player_bullet", [0.8, 0.6, 0.7]),
+				("enemy_hull", [0.8, 0.8, 0.8]),
+				("enemy_detail", [0.6, 0.6, 0.6]),
+				("enemy_bullet", [0.95, 0.9, 0.25]),
+				("enemy_bullet_a", [0.9, 0.6, 0.]),
+				("enemy_bullet_b", [0.35, 0.7, 0.9]),
+				("laser", [0., 0.75, 0.55]),
+				("hud_level", [0.35, 0.7, 0.9]),
+				("hud_level_drop", [0.84, 0.37, 0.]),
+				("hud_quick", [0.95, 0.9, 0.25]),
+				("hud_slow", [0.8, 0.6, 0.7]),
+			])),
+			"high_contrast" => Some(Theme::from_pairs(&[
+				("player", [1., 1., 1.]),
+				("player_hit", [1., 0., 0.]),
+				("player_bullet", [0., 1., 0.]),
+				("enemy_hull", [1., 1., 1.]),
+				("enemy_detail", [1., 0.5, 0.]),
+				("enemy_bullet", [1., 1., 1.]),
+				("enemy_bullet_a", [1., 1., 0.]),
+				("enemy_bullet_b", [0., 1., 1.]),
+				("laser", [1., 0., 1.]),
+				("hud_level", [0., 1., 1.]),
+				("hud_level_drop", [1., 0., 0.]),
+				("hud_quick", [1., 1., 0.]),
+				("hud_slow", [1., 0., 1.]),
+				("hud_text", [1., 1., 1.]),
+				("hud_highlight", [1., 1., 0.]),
+			])),
+			_ => None,
+		}
+	}
+
+	// toml table of role = [r, g, b]
+	pub fn load(filename: &str) -> Result<Theme, String> {
+		let string = std::fs::read_to_string(filename).map_err(|e| e.to_string())?;
+		let colors: HashMap<String, [f32; 3]> =
+			toml::from_str(&string).map_err(|e| e.to_string())?;
+		for role in colors.keys() {
+			if !ROLES.contains(&role.as_str()) {
+				println!("Unknown color role \"{}\" in {}", role, filename);
+			}
+		}
+		Ok(Theme { colors })
+	}
+
+	// arg is either a preset name or a theme file
+	pub fn from_arg(arg: &str) -> Result<Theme, String> {
+		match Theme::preset(arg) {
+			Some(theme) => Ok(theme),
+			None => Theme::load(arg),
+		}
+	}
+}
+
+lazy_static! {
+	static ref THEME: RwLock<Theme> = RwLock::new(Default::default());
+}
+
+// must be called before any graphics are built
+pub fn set_theme(theme: Theme) {
+	*THEME.write().unwrap() = theme;
+}
+
+pub fn color(role: &str, default: [f32; 3]) -> [f32; 3] {
+	THEME
+		.read()
+		.unwrap()
+		.colors
+		.get(role)
+		.copied()
+		.unwrap_or(default)
+}
+
+pub fn color_alpha(role: &str, default: [f32; 4]) -> [f32; 4] {
+	let [r, g, b] = color(role, [default[0], default[1], default[2]]);
+	[r, g, b, default[3]]
+}
+
+// replace rgb of a graphic string like "l r g b a x1 y1 ...", alpha is kept
+fn themed_str(theme: &Theme, role: &str, string: &str) -> String {
+	let mut tokens: Vec<String> = string.split_whitespace().map(|x| x.to_string()).collect();
+	if let Some(color) = theme.colors.get(role) {
+		for i in 0..3 {
+			tokens[i + 1] = color[i].to_string();
+		}
+	}
+	tokens.join(" ")
+}
+
+// graphic strings, each with a color role
+pub fn themed_graphic_objects(strs: Vec<(&str, &str)>) -> GraphicObjects {
+	let theme = THEME.read().unwrap();
+	let strings: Vec<String> = strs
+		.iter()
+		.map(|(role, string)| themed_str(&theme, role, string))
+		.collect();
+	GraphicObjects::from_strs(strings.iter().map(|x| x.as_str()).collect())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_themed_str() {
+		let string = "l 1 1 0.2 0.7 -10 -5 10 0";
+		// global theme is left alone, other tests build graphics in parallel
+		assert_eq!(
+			themed_str(&Default::default(), "enemy_bullet_a", string),
+			string
+		);
+		let theme = Theme::preset("high_contrast").unwrap();
+		assert_eq!(
+			themed_str(&theme, "enemy_bullet_a", string),
+			"l 1 1 0 0.7 -10 -5 10 0"
+		);
+		assert_eq!(themed_str(&theme, "no_such_role", string), string);
+	}
+}
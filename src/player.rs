@@ -3,7 +3,9 @@ use std::collections::VecDeque;
 use crate::algebra::Point2f;
 use crate::bullet::Bullet;
 use crate::cannon::SimpleCannon;
+use crate::game_rules::GameRules;
 use crate::graphic_object::{GraphicObjects, GraphicObjectsIntoIter};
+use crate::palette::themed_graphic_objects;
 use crate::window_rect::{WINDOW_RECT, WINDOW_SIZE};
 
 pub struct Player {
@@ -25,10 +27,11 @@ pub struct Player {
 
 	hit_reset: f32,
 	hit_reset_timer: f32,
+	hitbox_r: f32,
 }
 
 impl Player {
-	pub fn new() -> Player {
+	pub fn new(rules: &GameRules) -> Player {
 		let window_size = *WINDOW_SIZE;
 		let p0 = Point2f::from_floats(window_size.x / 2., window_size.y - 50.) + WINDOW_RECT.lu;
 		Player {
@@ -40,16 +43,19 @@ impl Player {
 				SimpleCannon::new(Point2f::from_floats(3., -5.), -2500., 0.05, false),
 			],
 			cannons_switch: false,
-			graphic_objects: GraphicObjects::from_strs(vec![
+			graphic_objects: themed_graphic_objects(vec![(
+				"player",
 				"l 0.3 1 1 1 -10 8 0 -10 10 8 3 4 -3 4 -10 8",
-			]),
-			graphic_objects_hit: GraphicObjects::from_strs(vec![
+			)]),
+			graphic_objects_hit: themed_graphic_objects(vec![(
+				"player_hit",
 				"l 1 0 0 1 -10 8 0 -10 10 8 3 4 -3 4 -10 8",
-			]),
-			speed_fast: 1000.0,
-			speed_slow: 500.0,
-			hit_reset: 0.15,
+			)]),
+			speed_fast: rules.speed_fast,
+			speed_slow: rules.speed_slow,
+			hit_reset: rules.hit_reset,
 			hit_reset_timer: 0.,
+			hitbox_r: rules.player_hitbox_r,
 		}
 	}
 
@@ -61,6 +67,10 @@ impl Player {
 		self.last_p
 	}
 
+	pub fn get_hitbox_r(&self) -> f32 {
+		self.hitbox_r
+	}
+
 	// set_dp is executed before frame update
 	fn set_dp(&mut self, directions: [bool; 4]) {
 		let mut dp = Point2f::new();
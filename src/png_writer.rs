@@ -0,0 +1,12 @@
+use std::fs::File;
+use std::io::BufWriter;
+
+// data is RGB24, the same as canvas
+pub fn save_png(filename: &str, size: (u32, u32), data: &[u8]) -> Result<(), String> {
+	let file = File::create(filename).map_err(|e| e.to_string())?;
+	let mut encoder = png::Encoder::new(BufWriter::new(file), size.0, size.1);
+	encoder.set_color(png::ColorType::RGB);
+	encoder.set_depth(png::BitDepth::Eight);
+	let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+	writer.write_image_data(data).map_err(|e| e.to_string())
+}
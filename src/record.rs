@@ -3,27 +3,117 @@ use std::io::prelude::*;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+use crate::cannon::CannonType;
+use crate::difficulty_manager::DifficultyCurve;
+use crate::game_rules::GameRules;
+use crate::timeline::Timeline;
+
+// replay files start with magic and format version,
+// bump version whenever Record layout changes
+const MAGIC: &[u8; 4] = b"EYHV";
+const VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Params {
+	pub seed: u64,
+	pub start_difficulty: f32,
+	pub curve: DifficultyCurve,
+	pub difficulty_drop: f32,
+}
+
+impl Default for Params {
+	fn default() -> Params {
+		Params {
+			seed: 0,
+			start_difficulty: 0.2,
+			curve: DifficultyCurve::Linear { growth: 0.0005 },
+			difficulty_drop: 0.002,
+		}
+	}
+}
+
+// practice sessions have pinned difficulty and are not scored
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Practice {
+	// hits are counted but take no effect
+	pub invincible: bool,
+	// wave scheme ids and cannon types allowed, None means all
+	pub waves: Option<Vec<u32>>,
+	pub cannon_types: Option<Vec<CannonType>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Record {
 	// e.g. dt_seq 1. 1. 1.
 	// times 1. 2. 3.
 	// operations[0] is executed in times = 1.
 	// so when an operation is recorded, that frame's tick() has not been called
-	pub params: (u64, f32, f32, f32),
+	pub params: Params,
+	pub rules: GameRules,
+	pub practice: Option<Practice>,
 	pub dt_seq: Vec<f32>,
 	pub operation: Vec<(usize, i8, bool)>, //frame, key_id, updown
+	// filled in on save, drawn and seeked on in replay
+	pub timeline: Timeline,
 }
 
 impl Record {
+	fn encode(&self) -> Vec<u8> {
+		let mut buffer = MAGIC.to_vec();
+		buffer.extend_from_slice(&VERSION.to_le_bytes());
+		buffer.extend(bincode::serialize(self).unwrap());
+		buffer
+	}
+
+	fn decode(buffer: &[u8]) -> Result<Record, String> {
+		if buffer.len() < 8 || &buffer[..4] != MAGIC {
+			return Err("not a replay, or saved by an older version".to_string());
+		}
+		let mut version = [0; 4];
+		version.copy_from_slice(&buffer[4..8]);
+		let version = u32::from_le_bytes(version);
+		if version != VERSION {
+			return Err(format!(
+				"replay format {} is not supported, expected {}",
+				version, VERSION
+			));
+		}
+		bincode::deserialize(&buffer[8..]).map_err(|e| e.to_string())
+	}
+
 	pub fn save(&self, filename: String) {
 		let mut file = File::create(&filename).unwrap();
-		file.write_all(&bincode::serialize(self).unwrap()).unwrap();
+		file.write_all(&self.encode()).unwrap();
 	}
 
-	pub fn load(filename: String) -> Record {
-		let mut file = File::open(&filename).unwrap();
+	pub fn load(filename: String) -> Result<Record, String> {
 		let mut buffer = Vec::<u8>::new();
-		file.read_to_end(&mut buffer).unwrap();
-		bincode::deserialize(&buffer).unwrap()
+		File::open(&filename)
+			.and_then(|mut file| file.read_to_end(&mut buffer))
+			.map_err(|e| e.to_string())?;
+		Record::decode(&buffer)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_record_format() {
+		let record = Record {
+			dt_seq: vec![0.5, 0.25],
+			operation: vec![(1, 5, true)],
+			..Default::default()
+		};
+		let buffer = record.encode();
+		let decoded = Record::decode(&buffer).unwrap();
+		assert_eq!(decoded.dt_seq, record.dt_seq);
+		assert_eq!(decoded.operation, record.operation);
+		// headerless replays of older versions are rejected instead of misread
+		assert!(Record::decode(&bincode::serialize(&record).unwrap()).is_err());
+		let mut newer = buffer.clone();
+		newer[4] = 3;
+		assert!(Record::decode(&newer).is_err());
 	}
 }
@@ -0,0 +1,97 @@
+use crate::graphic_object::{GraphicObject, LineSegs2f, Polygon2f};
+
+// time constant of phosphor decay, in seconds
+const PERSISTENCE: f32 = 0.06;
+// bloom is computed on a downsampled frame
+const BLOOM_DOWNSAMPLE: usize = 4;
+const BLOOM_RADIUS: usize = 2;
+const BLOOM_GAIN: f32 = 0.8;
+
+// polygons are drawn as closed outlines
+pub fn outline(graphic_object: Box<dyn GraphicObject>) -> Box<dyn GraphicObject> {
+	match graphic_object.as_any().downcast_ref::<Polygon2f>() {
+		None => graphic_object,
+		Some(polygon) => {
+			let mut vertices = polygon.vertices.clone();
+			if let Some(first) = vertices.first() {
+				vertices.push(*first);
+			}
+			Box::new(LineSegs2f {
+				vertices,
+				color: polygon.border_color,
+			})
+		}
+	}
+}
+
+// fade last frame instead of clearing it
+pub fn decay(data: &mut [u8], dt: f32) {
+	let k = ((-dt / PERSISTENCE).exp() * 256.) as u16;
+	for value in data.iter_mut() {
+		*value = ((*value as u16 * k) >> 8) as u8;
+	}
+}
+
+// box blur of blocks, separable
+fn blur(low: &[f32], size: (usize, usize)) -> Vec<f32> {
+	let (w, h) = size;
+	let mut horizontal = vec![0f32; low.len()];
+	for y in 0..h {
+		for x in 0..w {
+			let (x0, x1) = (
+				x.saturating_sub(BLOOM_RADIUS),
+				(x + BLOOM_RADIUS).min(w - 1),
+			);
+			for c in 0..3 {
+				let sum: f32 = (x0..=x1).map(|xx| low[(y * w + xx) * 3 + c]).sum();
+				horizontal[(y * w + x) * 3 + c] = sum / (2 * BLOOM_RADIUS + 1) as f32;
+			}
+		}
+	}
+	let mut result = vec![0f32; low.len()];
+	for y in 0..h {
+		let (y0, y1) = (
+			y.saturating_sub(BLOOM_RADIUS),
+			(y + BLOOM_RADIUS).min(h - 1),
+		);
+		for x in 0..w {
+			for c in 0..3 {
+				let sum: f32 = (y0..=y1).map(|yy| horizontal[(yy * w + x) * 3 + c]).sum();
+				result[(y * w + x) * 3 + c] = sum / (2 * BLOOM_RADIUS + 1) as f32;
+			}
+		}
+	}
+	result
+}
+
+// output = data + blurred data
+pub fn bloom(data: &[u8], size: (u32, u32), output: &mut Vec<u8>) {
+	let (w, h) = (size.0 as usize, size.1 as usize);
+	let (lw, lh) = (w.div_ceil(BLOOM_DOWNSAMPLE), h.div_ceil(BLOOM_DOWNSAMPLE));
+	let mut low = vec![0f32; lw * lh * 3];
+	for y in 0..h {
+		for x in 0..w {
+			let block = (y / BLOOM_DOWNSAMPLE) * lw + x / BLOOM_DOWNSAMPLE;
+			for c in 0..3 {
+				low[block * 3 + c] += data[(y * w + x) * 3 + c] as f32;
+			}
+		}
+	}
+	let block_area = (BLOOM_DOWNSAMPLE * BLOOM_DOWNSAMPLE) as f32;
+	for value in low.iter_mut() {
+		*value /= block_area;
+	}
+	let low = blur(&low, (lw, lh));
+	output.clear();
+	output.extend_from_slice(data);
+	for y in 0..h {
+		for x in 0..w {
+			let block = (y / BLOOM_DOWNSAMPLE) * lw + x / BLOOM_DOWNSAMPLE;
+			for c in 0..3 {
+				let offset = (y * w + x) * 3 + c;
+				output[offset] =
+					(data[offset] as f32 + low[block * 3 + c] * BLOOM_GAIN).min(255.) as u8;
+			}
+		}
+	}
+}
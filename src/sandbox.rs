@@ -0,0 +1,151 @@
+use crate::algebra::Point2f;
+use crate::background::Background;
+use crate::bullet_pool::BulletPool;
+use crate::cannon::{self, CannonType, CANNON_TYPES};
+use crate::display::Display;
+use crate::enemy::enemy_prototype;
+use crate::enemy::{Enemy, EnemyTickReturnOption};
+use crate::enemy_path::EnemyPath;
+use crate::graphic_object::{generate_thick_arc, GraphicObjects, GraphicObjectsIntoIter};
+use crate::menu::MenuKey;
+use crate::text::text_graphic_objects;
+use crate::window_rect::WINDOW_SIZE;
+
+// a stationary enemy with a single cannon, for viewing cannons
+// generated with given type, seed and difficulty
+pub struct Sandbox {
+	cannon_type: CannonType,
+	seed: u64,
+	difficulty: f32,
+	enemy_p: Point2f,
+	// cannons aim at target instead of player
+	target_p: Point2f,
+
+	enemy: Enemy,
+	parameters: Vec<(&'static str, f32)>,
+	enemy_bullet_pool: BulletPool,
+	background: Background,
+}
+
+impl Sandbox {
+	pub fn new(cannon_type: CannonType, seed: u64, difficulty: f32, enemy_p: Point2f) -> Sandbox {
+		let (enemy, parameters) = Sandbox::build_enemy(cannon_type, seed, difficulty, enemy_p);
+		Sandbox {
+			cannon_type,
+			seed,
+			difficulty,
+			enemy_p,
+			target_p: Point2f::from_floats(WINDOW_SIZE.x / 2., WINDOW_SIZE.y * 0.8),
+			enemy,
+			parameters,
+			enemy_bullet_pool: BulletPool::new(),
+			background: Background::new(),
+		}
+	}
+
+	fn build_enemy(
+		cannon_type: CannonType,
+		seed: u64,
+		difficulty: f32,
+		enemy_p: Point2f,
+	) -> (Enemy, Vec<(&'static str, f32)>) {
+		// same correlation as random_mapper
+		let mut cannon = cannon::generate(cannon_type, seed, difficulty, 0.5);
+		cannon.set_rng(seed);
+		let parameters = cannon.parameters();
+		let prototype = &enemy_prototype::SMALL;
+		let enemy = Enemy::new(
+			EnemyPath::stationary(enemy_p),
+			prototype.speed,
+			f32::INFINITY,
+			vec![cannon],
+			prototype.graphic_objects_options[0].clone(),
+			prototype.hitboxes.clone(),
+		);
+		(enemy, parameters)
+	}
+
+	fn rebuild(&mut self) {
+		let (enemy, parameters) =
+			Sandbox::build_enemy(self.cannon_type, self.seed, self.difficulty, self.enemy_p);
+		self.enemy = enemy;
+		self.parameters = parameters;
+		self.enemy_bullet_pool = BulletPool::new();
+	}
+
+	pub fn set_enemy_p(&mut self, p: Point2f) {
+		self.enemy_p = p;
+		self.rebuild();
+	}
+
+	pub fn set_target_p(&mut self, p: Point2f) {
+		self.target_p = p;
+	}
+
+	// return false to exit
+	pub fn proc_key(&mut self, key: MenuKey) -> bool {
+		match key {
+			MenuKey::Left => self.difficulty = (self.difficulty - 0.01).max(0.),
+			MenuKey::Right => self.difficulty = (self.difficulty + 0.01).min(1.),
+			MenuKey::Up => self.seed = self.seed.wrapping_add(1),
+			MenuKey::Down => self.seed = self.seed.wrapping_sub(1),
+			MenuKey::Confirm => {
+				let id = CANNON_TYPES
+					.iter()
+					.position(|cannon_type| *cannon_type == self.cannon_type)
+					.unwrap();
+				self.cannon_type = CANNON_TYPES[(id + 1) % CANNON_TYPES.len()];
+			}
+			MenuKey::Back => return false,
+			_ => return true,
+		}
+		self.rebuild();
+		true
+	}
+
+	pub fn tick(&mut self, dt: f32) {
+		self.background.tick(dt, false);
+		self.enemy_bullet_pool.tick(dt);
+		if let EnemyTickReturnOption::Normal(bullet_queue) = self.enemy.tick(dt, self.target_p) {
+			self.enemy_bullet_pool.extend(bullet_queue);
+		}
+	}
+
+	pub fn graphic_objects_iter(&self) -> GraphicObjectsIntoIter {
+		const TEXT_SIZE: f32 = 12.;
+		let mut graphic_objects: GraphicObjects = Default::default();
+		graphic_objects.extend(self.enemy.get_shifted_graphic_objects());
+		graphic_objects.extend(generate_thick_arc(
+			self.target_p,
+			(6., 8.),
+			(0., 2. * std::f32::consts::PI),
+			Some([1., 0.3, 0.3, 1.]),
+			Some([1., 0.3, 0.3, 0.3]),
+		));
+		let mut lines = vec![
+			self.cannon_type.name().to_uppercase(),
+			format!("SEED {}", self.seed),
+			format!("DIFFICULTY {:.2}", self.difficulty),
+		];
+		lines.extend(
+			self.parameters
+				.iter()
+				.map(|(name, value)| format!("{} {:.3}", name.to_uppercase(), value)),
+		);
+		for (i, line) in lines.iter().enumerate() {
+			graphic_objects.extend(text_graphic_objects(
+				line,
+				TEXT_SIZE,
+				Point2f::from_floats(10., 10. + i as f32 * TEXT_SIZE * 1.5),
+				Some([1., 1., 1., 0.7]),
+			));
+		}
+		graphic_objects.into_iter()
+	}
+
+	pub fn render(&self, display: &mut Display) {
+		display.draw(self.background.graphic_objects_iter());
+		display.draw(self.graphic_objects_iter());
+		display.draw(self.enemy_bullet_pool.graphic_objects_iter());
+	}
+}
@@ -0,0 +1,89 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::display::Display;
+use crate::key_binding::Action;
+use crate::png_writer::save_png;
+use crate::record::Record;
+use crate::session::Session;
+
+pub fn subcommand() -> App<'static, 'static> {
+	SubCommand::with_name("screenshot")
+		.about("play a replay headless and save a frame to png")
+		.arg(Arg::with_name("replay").required(true).help("replay file"))
+		.arg(
+			Arg::with_name("frame")
+				.long("frame")
+				.takes_value(true)
+				.help("number of ticks played before the screenshot, default 0"),
+		)
+		.arg(
+			Arg::with_name("out")
+				.short("o")
+				.long("out")
+				.takes_value(true)
+				.help("output png, default screenshot.png"),
+		)
+		.arg(
+			Arg::with_name("scale")
+				.long("scale")
+				.takes_value(true)
+				.help("canvas scaler, default 1"),
+		)
+		.arg(
+			Arg::with_name("hitboxes")
+				.long("hitboxes")
+				.help("show hitbox debug overlay"),
+		)
+		.arg(
+			Arg::with_name("panel")
+				.long("panel")
+				.help("show side panel"),
+		)
+		.arg(
+			Arg::with_name("retro")
+				.long("retro")
+				.help("retro vector-line style"),
+		)
+}
+
+pub fn run(matches: &ArgMatches) {
+	let frame = matches
+		.value_of("frame")
+		.map(|frame| frame.parse::<usize>().unwrap())
+		.unwrap_or(0);
+	let out = matches.value_of("out").unwrap_or("screenshot.png");
+	let scaler = matches
+		.value_of("scale")
+		.map(|scale| scale.parse::<f32>().unwrap())
+		.unwrap_or(1.);
+	let mut display = Display::new(
+		scaler,
+		matches.is_present("retro"),
+		matches.is_present("panel"),
+	);
+	let replay = matches.value_of("replay").unwrap();
+	let mut session = Session::from_record(
+		Record::load(replay.to_string())
+			.unwrap_or_else(|e| panic!("Failed to load replay {}: {}", replay, e)),
+	);
+	session.set_headless(true);
+	if matches.is_present("hitboxes") {
+		session.proc_key(Action::DebugOverlay.key_id().unwrap(), true);
+	}
+	for tick in 0..frame {
+		if !session.tick(0.) {
+			panic!("Replay ended at frame {}", tick);
+		}
+	}
+	display.begin_frame(0.);
+	session.render(&mut display);
+	let size = display.get_size();
+	save_canvas(out, size, display.get_data());
+}
+
+pub fn save_canvas(filename: &str, size: (u32, u32), data: &[u8]) {
+	match save_png(filename, size, data) {
+		Ok(()) => println!("Screenshot saved to {}", filename),
+		Err(e) => println!("Failed to save screenshot {}: {}", filename, e),
+	}
+}
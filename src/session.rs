@@ -1,24 +1,33 @@
 #[allow(unused_imports)]
 use crate::collision::CollisionPipeInterface; // for memleak
 
+use crate::agent::{point, CircleObservation, Observation};
+use crate::algebra::Point2f;
+use crate::audio::{Music, SoundEvent};
 use crate::background::Background;
 use crate::bullet_pool::BulletPool;
 use crate::canvas::Canvas;
-use crate::collision::{collision_enemy, collision_player};
+use crate::collision::{collision_enemy, collision_player, SweptCircle};
+use crate::debug_overlay::DebugOverlay;
 use crate::destroy_effect::DestroyedObjects;
-use crate::difficulty_manager::{DifficultyManager, DIFFICULTY_MULTIPLIER};
+use crate::difficulty_manager::{DifficultyCurve, DifficultyManager, DIFFICULTY_MULTIPLIER};
+use crate::display::Display;
 use crate::enemy_pool::EnemyPool;
 use crate::file_manager::FileManager;
 use crate::fps_indicator::FpsIndicator;
+use crate::game_rules::GameRules;
 use crate::graphic_object::{generate_thick_arc, GraphicObject, GraphicObjectsIntoIter};
 use crate::key_state::KeyState;
 use crate::player::Player;
-use crate::record::Record;
+use crate::record::{Params, Practice, Record};
+use crate::side_panel::{side_panel_graphic_objects, PanelInfo};
 use crate::slowdown_manager::SlowdownManager;
+use crate::stats::{Event, Sample, Stats};
 use crate::status_bar::StatusBar;
 use crate::time_manager::TimeManager;
+use crate::timeline::Timeline;
 use crate::wave_generator::WaveGenerator;
-use crate::window_rect::{SCALER, WINDOW_SIZE};
+use crate::window_rect::TIMELINE_RECT;
 
 pub struct SessionGraphicObjectsIter {
 	background_iter: GraphicObjectsIntoIter,
@@ -83,10 +92,17 @@ pub struct Session {
 	// ticks, operations
 	replay: Option<(usize, usize)>,
 	fast_replay: bool,
+	// no frame limiter and nothing saved on exit
+	headless: bool,
+	// unscaled game time
+	timer: f32,
+	// total unscaled time of replay
+	replay_length: f32,
 
 	difficulty_manager: DifficultyManager,
 	current_difficulty: f32,
 	wave_generator: WaveGenerator,
+	hits: u32,
 
 	// control
 	key_state: KeyState,
@@ -96,121 +112,77 @@ pub struct Session {
 	time_manager: TimeManager,
 	status_bar: StatusBar,
 	fps_indicator: FpsIndicator,
+	debug_overlay: DebugOverlay,
 	background: Background,
+	music: Music,
 
-	pub canvas: Canvas,
-
-	#[allow(dead_code)]
-	session_info: (u64, f32, f32, f32),
+	// sounds of last tick
+	sound_events: Vec<SoundEvent>,
+	stats: Stats,
+	timeline: Timeline,
+	// time under replay timeline being dragged, seeked to on release
+	seek_target: Option<f32>,
 }
 
 impl Session {
-	pub fn new() -> Session {
-		use clap::{App, Arg};
-		let matches = App::new("eyhv: Shoot 'em up game inspired by PARSEC47")
-			.arg(
-				Arg::with_name("seed")
-					.short("s")
-					.long("seed")
-					.takes_value(true)
-					.help("random seed used"),
-			)
-			.arg(
-				Arg::with_name("start difficulty")
-					.short("d")
-					.long("start-difficulty")
-					.takes_value(true)
-					.help("difficulty at start"),
-			)
-			.arg(
-				Arg::with_name("difficulty growth")
-					.short("g")
-					.long("difficulty-growth")
-					.takes_value(true)
-					.help("difficulty growth per second"),
-			)
-			.arg(
-				Arg::with_name("difficulty drop")
-					.short("D")
-					.long("difficulty-drop")
-					.takes_value(true)
-					.help("difficulty drop on each hit"),
-			)
-			.arg(
-				Arg::with_name("replay file")
-					.short("f")
-					.long("replay-file")
-					.takes_value(true)
-					.help("enable replay mode and load replay file(other args will be suppressed)"),
-			)
-			.get_matches();
-		let seed = match matches.value_of("seed") {
-			None => {
-				use rand::Rng;
-				use rand::SeedableRng;
-				let mut rng = rand_pcg::Pcg64Mcg::from_entropy();
-				let seed = rng.gen::<u64>();
-				println!("Seed generated: {}", seed);
-				seed
-			}
-			Some(seed) => seed.parse::<u64>().unwrap(),
-		};
-		let start_difficulty = match matches.value_of("start difficulty") {
-			None => {
-				println!("Using default start difficulty 0.24");
-				0.2
-			}
-			Some(start_difficulty) => start_difficulty.parse::<f32>().unwrap(),
-		};
-		let difficulty_growth = match matches.value_of("difficulty growth") {
-			None => {
-				println!("Using default difficulty growth rate 0.0006");
-				0.0005
-			}
-			Some(difficulty_growth) => difficulty_growth.parse::<f32>().unwrap(),
-		};
-		let difficulty_drop = match matches.value_of("difficulty drop") {
-			None => 0.002,
-			Some(difficulty_drop) => difficulty_drop.parse::<f32>().unwrap(),
-		};
-		let replay: Option<String> = matches.value_of("replay file").map(|s| s.to_string());
-		let params;
-		let mut record: Record;
-		let replay = match replay {
-			None => {
-				record = Default::default();
-				params = (seed, start_difficulty, difficulty_growth, difficulty_drop);
-				record.params = params;
-				None
-			}
-			Some(replay_file) => {
-				record = Record::load(replay_file);
-				params = record.params;
-				Some((0, 0))
-			}
+	pub fn new(mut params: Params, rules: GameRules, practice: Option<Practice>) -> Session {
+		if practice.is_some() {
+			// practice pins difficulty
+			params.curve = DifficultyCurve::Linear { growth: 0. };
+			params.difficulty_drop = 0.;
+		}
+		let record = Record {
+			params,
+			rules,
+			practice,
+			..Default::default()
 		};
+		Session::build(record, None)
+	}
+
+	pub fn from_record(record: Record) -> Session {
+		Session::build(record, Some((0, 0)))
+	}
+
+	fn build(record: Record, replay: Option<(usize, usize)>) -> Session {
+		let params = record.params.clone();
+		let rules = record.rules.clone();
+		let practice = record.practice.clone().unwrap_or_default();
+		let replay_length = record.dt_seq.iter().sum();
 		Session {
-			player: Player::new(),
+			player: Player::new(&rules),
 			player_bullet_pool: BulletPool::new(),
 			enemy_pool: EnemyPool::new(),
-			destroyed_objects: DestroyedObjects::new(seed), //simply use the same seed
+			destroyed_objects: DestroyedObjects::new(params.seed), //simply use the same seed
 			enemy_bullet_pool: BulletPool::new(),
 			record,
 			file_manager: FileManager::new(),
 			replay,
 			fast_replay: false,
-			difficulty_manager: DifficultyManager::new(params.1, params.2, params.3),
-			current_difficulty: params.1,
-			wave_generator: WaveGenerator::new(params.0),
+			headless: false,
+			timer: 0.,
+			replay_length,
+			difficulty_manager: DifficultyManager::new(
+				params.start_difficulty,
+				params.curve,
+				params.difficulty_drop,
+			),
+			current_difficulty: params.start_difficulty,
+			wave_generator: WaveGenerator::new(params.seed, practice.waves, practice.cannon_types),
+			hits: 0,
 			key_state: KeyState::new(),
 			pause: false,
-			slowdown_manager: SlowdownManager::new(),
-			time_manager: TimeManager::new(),
-			status_bar: StatusBar::new(params.1),
+			slowdown_manager: SlowdownManager::new(&rules),
+			time_manager: TimeManager::new(&rules),
+			status_bar: StatusBar::new(params.start_difficulty),
 			fps_indicator: FpsIndicator::new(),
+			debug_overlay: DebugOverlay::new(),
 			background: Background::new(),
-			canvas: Canvas::new((WINDOW_SIZE.x as i32, WINDOW_SIZE.y as i32), *SCALER),
-			session_info: (seed, start_difficulty, difficulty_growth, difficulty_drop),
+			music: Music::new(params.seed),
+			sound_events: Vec::new(),
+			stats: Stats::new(),
+			timeline: Timeline::new(),
+			seek_target: None,
 		}
 	}
 
@@ -228,6 +200,7 @@ impl Session {
 	}
 
 	pub fn tick(&mut self, mut dt: f32) -> bool {
+		self.sound_events.clear();
 		if self.pause {
 			std::thread::sleep(std::time::Duration::new(0, 1_000_000_000u32 / 120));
 			return true;
@@ -264,7 +237,16 @@ impl Session {
 			}
 		}
 
-		self.time_manager.set_state(self.slowdown_manager.tick(dt));
+		self.timer += dt;
+		let slowing = self.slowdown_manager.tick(dt);
+		if slowing != self.time_manager.get_state() {
+			self.sound_events.push(if slowing {
+				SoundEvent::SlowdownStart
+			} else {
+				SoundEvent::SlowdownStop
+			});
+		}
+		self.time_manager.set_state(slowing);
 		let dt_scaled = dt * self.time_manager.update_and_get_dt_scaler(dt);
 
 		let (level_change, score_update) = self.difficulty_manager.tick(dt_scaled);
@@ -278,6 +260,12 @@ impl Session {
 						.to_string()
 				));
 				self.background.send_message("    LV-DOWN ".to_string());
+				self.stats.event(Event::LevelChange {
+					time: self.timer,
+					level: (self.current_difficulty * DIFFICULTY_MULTIPLIER) as u32,
+					up: false,
+				});
+				self.sound_events.push(SoundEvent::LevelDown);
 			}
 			std::cmp::Ordering::Greater => {
 				self.background.send_message(format!(
@@ -286,20 +274,39 @@ impl Session {
 						.to_string()
 				));
 				self.background.send_message("    LV-UP   ".to_string());
+				self.stats.event(Event::LevelChange {
+					time: self.timer,
+					level: (self.current_difficulty * DIFFICULTY_MULTIPLIER) as u32,
+					up: true,
+				});
+				self.sound_events.push(SoundEvent::LevelUp);
 			}
 		}
 
 		self.player_bullet_pool.tick(dt_scaled);
-		self.player_bullet_pool.extend(self.player.tick(
+		let player_bullets = self.player.tick(
 			dt_scaled,
 			self.key_state.directions,
 			self.time_manager.get_state(),
-		));
+		);
+		if !player_bullets.is_empty() {
+			self.sound_events.push(SoundEvent::Fire);
+		}
+		self.player_bullet_pool.extend(player_bullets);
 		self.enemy_pool
 			.extend(self.wave_generator.tick(dt_scaled, self.current_difficulty));
+		for scheme in self.wave_generator.get_spawned_waves().iter() {
+			self.stats.event(Event::WaveSpawn {
+				time: self.timer,
+				scheme: *scheme,
+			});
+		}
 		self.enemy_bullet_pool.tick(dt_scaled);
 		self.enemy_bullet_pool
 			.extend(self.enemy_pool.tick(dt_scaled, self.player.get_p()));
+		for sound in self.enemy_pool.get_fired_sounds().iter() {
+			self.sound_events.push(SoundEvent::Cannon(sound.clone()));
+		}
 
 		let slowdown_info = self.slowdown_manager.get_info();
 		self.status_bar.tick(
@@ -310,26 +317,72 @@ impl Session {
 			slowdown_info.2,
 			self.player.get_p(),
 		);
-		self.status_bar.score_update = score_update;
+		self.status_bar.score_update = score_update && self.record.practice.is_none();
 
 		self.fps_indicator.tick(dt);
+		self.debug_overlay.tick(dt);
 		self.background.tick(dt_scaled, slowdown_info.2);
+		// ticked in headless runs too, so that seeking keeps music in step,
+		// steps are only rendered by audio backend
+		for step in self.music.tick(
+			dt_scaled,
+			self.current_difficulty,
+			self.background.get_shift(),
+		) {
+			self.sound_events.push(SoundEvent::Music(step));
+		}
 		self.destroyed_objects.tick(dt_scaled);
-		collision_enemy(
+		let destroyed_count = collision_enemy(
 			&mut self.enemy_pool,
 			&mut self.player_bullet_pool,
 			&mut self.destroyed_objects,
 		);
+		for _ in 0..destroyed_count {
+			self.sound_events.push(SoundEvent::EnemyDestroyed);
+			self.stats.event(Event::Kill { time: self.timer });
+		}
 		// no need to calculate collision if hit_reset-ing
-		if !self.player.hit_reset()
-			&& collision_player(
+		let hit_bullet = if self.player.hit_reset() {
+			None
+		} else {
+			collision_player(
 				self.player.get_p(),
 				self.player.get_last_p(),
+				self.player.get_hitbox_r(),
 				&mut self.enemy_bullet_pool,
-			) {
-			self.player.hit();
-			self.difficulty_manager.drop();
-			self.status_bar.hit();
+			)
+		};
+		if let Some(hit_bullet) = hit_bullet {
+			self.debug_overlay.hit(
+				self.player_swept_circle(),
+				SweptCircle {
+					p: hit_bullet.get_p(),
+					last_p: hit_bullet.get_last_p(),
+					r: hit_bullet.get_r(),
+				},
+			);
+			self.stats.event(Event::Hit {
+				time: self.timer,
+				player: point(self.player.get_p()),
+				bullet: hit_bullet.name(),
+				cannon: hit_bullet
+					.get_cannon_type()
+					.map(|cannon_type| cannon_type.name()),
+			});
+			self.timeline.hit(self.timer);
+			self.sound_events.push(SoundEvent::PlayerHit);
+			self.hits += 1;
+			let invincible = self
+				.record
+				.practice
+				.as_ref()
+				.map(|practice| practice.invincible)
+				.unwrap_or(false);
+			if !invincible {
+				self.player.hit();
+				self.difficulty_manager.drop();
+				self.status_bar.hit();
+			}
 		}
 
 		// memleak monitor
@@ -340,7 +393,20 @@ impl Session {
 		//     self.enemy_pool.len(),
 		//     self.destroyed_objects.len(),
 		// );
-		if !self.fast_replay {
+		self.timeline
+			.tick(dt, self.timer, self.difficulty_manager.get_difficulty());
+		if self.stats.tick(dt) {
+			self.stats.sample(Sample {
+				time: self.timer,
+				difficulty: self.current_difficulty,
+				enemies: self.enemy_pool.len(),
+				enemy_bullets: self.enemy_bullet_pool.len(),
+				player_bullets: self.player_bullet_pool.len(),
+				slowing: self.time_manager.get_state(),
+				player: point(self.player.get_p()),
+			});
+		}
+		if !self.fast_replay && !self.headless {
 			std::thread::sleep(std::time::Duration::new(0, 1_000_000_000u32 / 120));
 		}
 
@@ -348,20 +414,104 @@ impl Session {
 	}
 
 	pub fn exit(&self) {
+		// a replay is already saved, and its stats are of the whole session
+		if self.headless || self.replay.is_some() {
+			return;
+		}
+		// practice is never scored and does not replace the latest replay
+		if self.record.practice.is_some() {
+			println!("Practice hits: {}", self.hits);
+			self.save(self.file_manager.get_practice_replay_path());
+			return;
+		}
 		println!(
 			"Score(max difficulty): {:?}",
 			self.difficulty_manager.get_max_difficulty()
 		);
-		self.record.save(self.file_manager.get_replay_path());
+		self.save(self.file_manager.get_replay_path());
+	}
+
+	// replay with stats next to it
+	pub fn save(&self, filename: String) {
+		self.stats.save(&filename);
+		let mut record = self.record.clone();
+		record.timeline = self.timeline.clone();
+		record.save(filename);
+	}
+
+	// replay only, seeking backwards rebuilds the session and replays from start
+	pub fn seek(&mut self, time: f32) {
+		if self.replay.is_none() {
+			return;
+		}
+		if time < self.timer {
+			let mut session = Session::build(std::mem::take(&mut self.record), Some((0, 0)));
+			session.fast_replay = self.fast_replay;
+			session.headless = self.headless;
+			std::mem::swap(&mut session.fps_indicator, &mut self.fps_indicator);
+			std::mem::swap(&mut session.debug_overlay, &mut self.debug_overlay);
+			*self = session;
+		}
+		let headless = self.headless;
+		self.headless = true;
+		while self.timer < time && self.tick(0.) {}
+		self.headless = headless;
+	}
+
+	// dragging on replay timeline only moves the cursor, p is in frame coordinates
+	pub fn drag_seek(&mut self, p: Point2f) {
+		if self.replay.is_none() || self.pause {
+			return;
+		}
+		// once started, dragging continues outside the graph
+		if self.seek_target.is_some() || TIMELINE_RECT.contain(p) {
+			self.seek_target = Some(self.record.timeline.time_at(&TIMELINE_RECT, p.x));
+		}
+	}
+
+	// seeking replays from start may take a while, so only on release
+	pub fn release_seek(&mut self) {
+		if let Some(time) = self.seek_target.take() {
+			self.seek(time);
+		}
+	}
+
+	pub fn get_timeline(&self) -> &Timeline {
+		&self.timeline
+	}
+
+	pub fn set_headless(&mut self, headless: bool) {
+		self.headless = headless;
+	}
+
+	pub fn sound_events(&self) -> &[SoundEvent] {
+		&self.sound_events
+	}
+
+	pub fn get_time(&self) -> f32 {
+		self.timer
 	}
 
-	fn toggle_pause(&mut self) {
-		self.pause = !self.pause;
+	pub fn get_hits(&self) -> u32 {
+		self.hits
+	}
+
+	pub fn get_player_p(&self) -> Point2f {
+		self.player.get_p()
+	}
+
+	pub fn is_paused(&self) -> bool {
+		self.pause
+	}
+
+	pub fn set_pause(&mut self, pause: bool) {
+		self.pause = pause;
 	}
 
 	pub fn proc_key(&mut self, key_id: i8, updown: bool) {
 		if key_id == 6 {
-			self.toggle_pause();
+			// resuming is done by pause menu
+			self.set_pause(true);
 			return;
 		}
 		if key_id == 7 {
@@ -374,6 +524,10 @@ impl Session {
 			self.fps_indicator.switch();
 			return;
 		}
+		if key_id == 9 {
+			self.debug_overlay.switch();
+			return;
+		}
 		if self.replay == None {
 			self.record
 				.operation
@@ -388,20 +542,80 @@ impl Session {
 		}
 	}
 
-	pub fn render(&mut self) {
-		if !self.pause {
-			self.canvas.flush();
-			for graphic_object in self.graphic_object_iter() {
-				graphic_object.render(&mut self.canvas);
-			}
+	fn player_swept_circle(&self) -> SweptCircle {
+		SweptCircle {
+			p: self.player.get_p(),
+			last_p: self.player.get_last_p(),
+			r: self.player.get_hitbox_r(),
+		}
+	}
+
+	pub fn panel_info(&self) -> PanelInfo {
+		PanelInfo {
+			difficulty: self.current_difficulty,
+			max_difficulty: self.difficulty_manager.get_max_difficulty(),
+			time: self.timer,
+			slowdown_reserves: self.slowdown_manager.get_reserves(),
+			hits: self.hits,
+			seed: self.record.params.seed,
+			replay_progress: self.replay.map(|_| (self.timer, self.replay_length)),
+		}
+	}
+
+	pub fn observation(&self) -> Observation {
+		let (quick, slow) = self.slowdown_manager.get_reserves();
+		Observation {
+			time: self.timer,
+			player: CircleObservation {
+				p: point(self.player.get_p()),
+				r: self.player.get_hitbox_r(),
+			},
+			enemies: self.enemy_pool.observations(),
+			enemy_bullets: self.enemy_bullet_pool.observations(),
+			slowdown_reserves: [quick, slow],
+			slowing: self.time_manager.get_state(),
+			difficulty: self.current_difficulty,
+			hits: self.hits,
+		}
+	}
+
+	pub fn get_record(&self) -> &Record {
+		&self.record
+	}
+
+	pub fn render(&self, display: &mut Display) {
+		display.draw(self.graphic_object_iter());
+		if self.debug_overlay.is_on() {
+			display.draw(
+				self.debug_overlay
+					.graphic_objects(
+						self.player_swept_circle(),
+						self.enemy_pool.swept_circles(),
+						self.enemy_bullet_pool.swept_circles(),
+						self.player_bullet_pool.swept_circles(),
+					)
+					.into_iter(),
+			);
+		}
+		if self.replay.is_some() {
+			let cursor = self.seek_target.unwrap_or(self.timer);
+			display.draw(
+				self.record
+					.timeline
+					.graphic_objects(&TIMELINE_RECT, Some(cursor))
+					.into_iter(),
+			);
+		}
+		if display.has_panel() {
+			display.draw_panel(side_panel_graphic_objects(&self.panel_info()).into_iter());
 		}
 	}
 
 	#[allow(dead_code)]
-	pub fn test_render(&mut self) {
+	pub fn test_render(&self, canvas: &mut Canvas) {
 		use crate::algebra::Point2f;
 		use crate::graphic_object::{LineSegs2f, Polygon2f};
-		self.canvas.flush();
+		canvas.flush();
 		let split = 90;
 		for k in 0..split {
 			let ang = std::f32::consts::PI * 2. / split as f32 * -k as f32;
@@ -415,12 +629,12 @@ impl Session {
 				250. + 200. * ang.cos(),
 				250. + 200. * ang.sin(),
 			])
-			.render(&mut self.canvas);
+			.render(canvas);
 		}
 		Polygon2f::from_floats(vec![
 			1., 1., 1., 1., 75., 50., 100., 50., 100., 100., 85., 75., 65., 75., 50., 100.,
 		])
-		.render(&mut self.canvas);
+		.render(canvas);
 		for graphic_object in generate_thick_arc(
 			Point2f::from_floats(200., 200.),
 			(83., 95.),
@@ -430,7 +644,7 @@ impl Session {
 		)
 		.into_iter()
 		{
-			graphic_object.render(&mut self.canvas);
+			graphic_object.render(canvas);
 		}
 	}
 }
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+use crate::file_manager::FileManager;
+use crate::window_rect::{scaled_size, WINDOW_SIZE};
+
+// display settings, saved in settings.toml in config dir
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Settings {
+	// window size when not fullscreen
+	pub window_width: u32,
+	pub window_height: u32,
+	pub fullscreen: bool,
+	// vector-line style with phosphor trails
+	pub retro: bool,
+	// numeric readouts right of the playfield
+	pub side_panel: bool,
+	// palette preset or theme file, loaded on start
+	pub theme: String,
+	// 0 to mute
+	pub volume: f32,
+}
+
+impl Default for Settings {
+	fn default() -> Settings {
+		// SCALER environment variable is kept for initial window size
+		let scaler = match std::env::var("SCALER") {
+			Ok(val) => val.parse::<f32>().unwrap(),
+			_ => 1.,
+		};
+		let (window_width, window_height) = scaled_size(scaler, *WINDOW_SIZE);
+		Settings {
+			window_width,
+			window_height,
+			fullscreen: false,
+			retro: false,
+			side_panel: false,
+			theme: "classic".to_string(),
+			volume: 0.5,
+		}
+	}
+}
+
+impl Settings {
+	pub fn load(file_manager: &FileManager) -> Settings {
+		let path = file_manager.get_config_path("settings.toml");
+		match std::fs::read_to_string(&path) {
+			Ok(string) => toml::from_str(&string).unwrap_or_else(|e| {
+				println!("Failed to parse {}: {}, using default settings", path, e);
+				Default::default()
+			}),
+			Err(_) => Default::default(),
+		}
+	}
+
+	pub fn save(&self, file_manager: &FileManager) {
+		let path = file_manager.get_config_path("settings.toml");
+		if std::fs::write(&path, toml::to_string(self).unwrap()).is_err() {
+			println!("Failed to write settings to {}", path);
+		}
+	}
+}
@@ -0,0 +1,104 @@
+use crate::algebra::Point2f;
+use crate::difficulty_manager::DIFFICULTY_MULTIPLIER;
+use crate::graphic_object::{GraphicObjects, LineSegs2f, Polygon2f};
+use crate::palette;
+use crate::text::text_graphic_objects;
+use crate::window_rect::PANEL_RECT;
+
+const LABEL_SIZE: f32 = 12.;
+const VALUE_SIZE: f32 = 20.;
+const MARGIN: f32 = 15.;
+
+// numeric readouts of a session
+pub struct PanelInfo {
+	pub difficulty: f32,
+	pub max_difficulty: Option<f32>,
+	// unscaled session time
+	pub time: f32,
+	// quick and slow slowdown pools, in seconds
+	pub slowdown_reserves: (f32, f32),
+	pub hits: u32,
+	pub seed: u64,
+	// current and total time, only in replay
+	pub replay_progress: Option<(f32, f32)>,
+}
+
+fn level(difficulty: f32) -> u32 {
+	(difficulty * DIFFICULTY_MULTIPLIER) as u32
+}
+
+// drawn in PANEL_RECT, right of the playfield
+pub fn side_panel_graphic_objects(info: &PanelInfo) -> GraphicObjects {
+	let x = PANEL_RECT.lu.x + MARGIN;
+	let width = PANEL_RECT.get_size().x - 2. * MARGIN;
+	let label_color = palette::color_alpha("hud_text", [0.6, 0.6, 0.7, 0.7]);
+	let value_color = palette::color_alpha("hud_text", [0.9, 0.9, 1., 1.]);
+	let mut graphic_objects: GraphicObjects = Default::default();
+	graphic_objects.push(Box::new(LineSegs2f {
+		vertices: vec![
+			Point2f::from_floats(PANEL_RECT.lu.x + 1., PANEL_RECT.lu.y),
+			Point2f::from_floats(PANEL_RECT.lu.x + 1., PANEL_RECT.rd.y),
+		],
+		color: label_color,
+	}));
+
+	let mut entries = vec![
+		("LV", level(info.difficulty).to_string()),
+		(
+			"MAX LV",
+			match info.max_difficulty {
+				Some(max_difficulty) => level(max_difficulty).to_string(),
+				None => "-".to_string(),
+			},
+		),
+		("TIME", format!("{:.1}", info.time)),
+		("QUICK", format!("{:.2}", info.slowdown_reserves.0)),
+		("SLOW", format!("{:.2}", info.slowdown_reserves.1)),
+		("HITS", info.hits.to_string()),
+		("SEED", info.seed.to_string()),
+	];
+	if let Some((current, total)) = info.replay_progress {
+		entries.push(("REPLAY", format!("{:.1}", current)));
+		entries.push(("OF", format!("{:.1}", total)));
+	}
+
+	let mut y = PANEL_RECT.lu.y + MARGIN;
+	for (label, value) in entries.iter() {
+		graphic_objects.extend(text_graphic_objects(
+			label,
+			LABEL_SIZE,
+			Point2f::from_floats(x, y),
+			Some(label_color),
+		));
+		y += LABEL_SIZE * 1.5;
+		// long values like seed are shrunk to fit
+		let size = VALUE_SIZE.min(width / value.chars().count() as f32);
+		graphic_objects.extend(text_graphic_objects(
+			value,
+			size,
+			Point2f::from_floats(x, y),
+			Some(value_color),
+		));
+		y += VALUE_SIZE * 1.8;
+	}
+
+	if let Some((current, total)) = info.replay_progress {
+		let ratio = if total > 0. {
+			(current / total).min(1.)
+		} else {
+			1.
+		};
+		let (x1, y1) = (x + width * ratio, y + 6.);
+		graphic_objects.push(Box::new(Polygon2f {
+			vertices: vec![
+				Point2f::from_floats(x, y),
+				Point2f::from_floats(x1, y),
+				Point2f::from_floats(x1, y1),
+				Point2f::from_floats(x, y1),
+			],
+			color: value_color,
+			border_color: value_color,
+		}));
+	}
+	graphic_objects
+}
@@ -1,3 +1,5 @@
+use crate::game_rules::GameRules;
+
 pub struct SlowdownManager {
 	quick: f32,
 	slow: f32,
@@ -10,17 +12,15 @@ pub struct SlowdownManager {
 }
 
 impl SlowdownManager {
-	pub fn new() -> SlowdownManager {
-		const QUICK_MAX: f32 = 1.;
-		const SLOW_MAX: f32 = 8.;
+	pub fn new(rules: &GameRules) -> SlowdownManager {
 		SlowdownManager {
-			quick: QUICK_MAX,
+			quick: rules.quick_max,
 			slow: 0.,
 			slowing: false,
-			quick_max: QUICK_MAX,
-			slow_max: SLOW_MAX,
-			quick_replenish_rate: 1.,
-			slow_replenish_rate: 0.3,
+			quick_max: rules.quick_max,
+			slow_max: rules.slow_max,
+			quick_replenish_rate: rules.quick_replenish_rate,
+			slow_replenish_rate: rules.slow_replenish_rate,
 		}
 	}
 
@@ -73,6 +73,11 @@ impl SlowdownManager {
 		false
 	}
 
+	// remaining seconds of quick and slow pools
+	pub fn get_reserves(&self) -> (f32, f32) {
+		(self.quick, self.slow)
+	}
+
 	pub fn get_info(&self) -> (f32, f32, bool) {
 		(
 			self.quick / self.quick_max,
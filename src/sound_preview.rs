@@ -0,0 +1,85 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::audio::music::render_music;
+use crate::audio::synth::{cannon_sound_strs, Sound};
+use crate::audio::{SAMPLE_RATE, SOUND_EVENTS};
+use crate::cannon::{self, CANNON_TYPES};
+use crate::wav_writer::save_wav;
+
+pub fn subcommand() -> App<'static, 'static> {
+	SubCommand::with_name("sounds")
+		.about("render all sound effects to wav without playing")
+		.arg(
+			Arg::with_name("out")
+				.short("o")
+				.long("out")
+				.takes_value(true)
+				.help("output directory, default sounds"),
+		)
+		.arg(
+			Arg::with_name("seed")
+				.short("s")
+				.long("seed")
+				.takes_value(true)
+				.help("random seed of cannons and music, default 0"),
+		)
+		.arg(
+			Arg::with_name("difficulty")
+				.short("d")
+				.long("difficulty")
+				.takes_value(true)
+				.help("difficulties of cannons and music, default \"0.2,0.5,0.8\""),
+		)
+}
+
+fn save(filename: &str, strs: &[String]) {
+	let samples = Sound::from_strs(strs)
+		.unwrap_or_else(|e| panic!("Failed to parse sound {}: {}", filename, e))
+		.render();
+	save_wav(filename, &samples, SAMPLE_RATE as u32)
+		.unwrap_or_else(|e| panic!("Failed to write {}: {}", filename, e));
+	println!("{}: {}", filename, strs.join(", "));
+}
+
+pub fn run(matches: &ArgMatches) {
+	let out = matches.value_of("out").unwrap_or("sounds");
+	let seed = matches
+		.value_of("seed")
+		.map(|seed| seed.parse::<u64>().unwrap())
+		.unwrap_or(0);
+	let difficulties = matches
+		.value_of("difficulty")
+		.unwrap_or("0.2,0.5,0.8")
+		.split(',')
+		.map(|x| x.parse::<f32>().unwrap())
+		.collect::<Vec<f32>>();
+	std::fs::create_dir_all(out).unwrap();
+	for event in SOUND_EVENTS.iter() {
+		let strs: Vec<String> = event.sound_strs().iter().map(|x| x.to_string()).collect();
+		save(&format!("{}/{}.wav", out, event.name()), &strs);
+	}
+	// cannons are generated as in waves
+	for cannon_type in CANNON_TYPES.iter() {
+		for difficulty in difficulties.iter() {
+			let cannon = cannon::generate(*cannon_type, seed, *difficulty, 0.5);
+			let strs = cannon_sound_strs(*cannon_type, &cannon.parameters());
+			save(
+				&format!("{}/{}_{:.2}.wav", out, cannon_type.name(), difficulty),
+				&strs,
+			);
+		}
+	}
+	// music of 8 seconds, in normal speed and in slowdown
+	for difficulty in difficulties.iter() {
+		for (suffix, shift) in [("", 0.), ("_slow", 1.)].iter() {
+			let filename = format!("{}/music_{:.2}{}.wav", out, difficulty, suffix);
+			save_wav(
+				&filename,
+				&render_music(seed, *difficulty, *shift, 8.),
+				SAMPLE_RATE as u32,
+			)
+			.unwrap_or_else(|e| panic!("Failed to write {}: {}", filename, e));
+			println!("{}", filename);
+		}
+	}
+}
@@ -0,0 +1,188 @@
+use std::fs::File;
+use std::io::prelude::*;
+
+use serde::Serialize;
+
+// seconds of unscaled time between samples
+const SAMPLE_INTERVAL: f32 = 1.;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Sample {
+	pub time: f32,
+	pub difficulty: f32,
+	pub enemies: usize,
+	pub enemy_bullets: usize,
+	pub player_bullets: usize,
+	pub slowing: bool,
+	pub player: [f32; 2],
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+	Hit {
+		time: f32,
+		player: [f32; 2],
+		bullet: &'static str,
+		// None if no cannon is recorded in bullet
+		cannon: Option<&'static str>,
+	},
+	Kill {
+		time: f32,
+	},
+	WaveSpawn {
+		time: f32,
+		scheme: u32,
+	},
+	LevelChange {
+		time: f32,
+		level: u32,
+		up: bool,
+	},
+}
+
+impl Event {
+	// time, name and detail, for csv
+	fn csv_fields(&self) -> (f32, &'static str, String) {
+		match self {
+			Event::Hit {
+				time,
+				player,
+				bullet,
+				cannon,
+			} => (
+				*time,
+				"hit",
+				format!(
+					"{} {} {} {}",
+					player[0],
+					player[1],
+					bullet,
+					cannon.unwrap_or("none")
+				),
+			),
+			Event::Kill { time } => (*time, "kill", String::new()),
+			Event::WaveSpawn { time, scheme } => (*time, "wave_spawn", scheme.to_string()),
+			Event::LevelChange { time, level, up } => (
+				*time,
+				"level_change",
+				format!("{} {}", level, if *up { "up" } else { "down" }),
+			),
+		}
+	}
+}
+
+// per second samples and events of a session
+#[derive(Serialize, Debug, Default)]
+pub struct Stats {
+	samples: Vec<Sample>,
+	events: Vec<Event>,
+	#[serde(skip)]
+	sample_timer: f32,
+}
+
+impl Stats {
+	pub fn new() -> Stats {
+		Default::default()
+	}
+
+	// return true if a sample is due
+	pub fn tick(&mut self, dt: f32) -> bool {
+		self.sample_timer -= dt;
+		if self.sample_timer <= 0. {
+			self.sample_timer += SAMPLE_INTERVAL;
+			true
+		} else {
+			false
+		}
+	}
+
+	pub fn sample(&mut self, sample: Sample) {
+		self.samples.push(sample);
+	}
+
+	pub fn event(&mut self, event: Event) {
+		self.events.push(event);
+	}
+
+	fn samples_csv(&self) -> String {
+		let mut string =
+			"time,difficulty,enemies,enemy_bullets,player_bullets,slowing,x,y\n".to_string();
+		for sample in self.samples.iter() {
+			string += &format!(
+				"{},{},{},{},{},{},{},{}\n",
+				sample.time,
+				sample.difficulty,
+				sample.enemies,
+				sample.enemy_bullets,
+				sample.player_bullets,
+				sample.slowing as u8,
+				sample.player[0],
+				sample.player[1],
+			);
+		}
+		string
+	}
+
+	fn events_csv(&self) -> String {
+		let mut string = "time,event,detail\n".to_string();
+		for event in self.events.iter() {
+			let (time, name, detail) = event.csv_fields();
+			string += &format!("{},{},{}\n", time, name, detail);
+		}
+		string
+	}
+
+	// <replay>.json has both, <replay>.csv has samples, <replay>.events.csv has events
+	pub fn save(&self, replay_filename: &str) {
+		for (filename, string) in [
+			(
+				format!("{}.json", replay_filename),
+				serde_json::to_string(self).unwrap(),
+			),
+			(format!("{}.csv", replay_filename), self.samples_csv()),
+			(format!("{}.events.csv", replay_filename), self.events_csv()),
+		]
+		.iter()
+		{
+			let mut file = File::create(filename).unwrap();
+			file.write_all(string.as_bytes()).unwrap();
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_stats() {
+		let mut stats = Stats::new();
+		let mut count = 0;
+		for _ in 0..300 {
+			if stats.tick(1. / 60.) {
+				count += 1;
+			}
+		}
+		// at 0, 1, 2, 3, 4 seconds
+		assert_eq!(count, 5);
+		stats.event(Event::LevelChange {
+			time: 1.,
+			level: 21,
+			up: true,
+		});
+		stats.event(Event::Hit {
+			time: 2.,
+			player: [250., 650.],
+			bullet: "simple_bullet",
+			cannon: Some("shotgun"),
+		});
+		assert_eq!(
+			stats.events_csv(),
+			"time,event,detail\n1,level_change,21 up\n2,hit,250 650 simple_bullet shotgun\n"
+		);
+		assert!(serde_json::to_string(&stats)
+			.unwrap()
+			.contains("{\"event\":\"level_change\",\"time\":1.0,\"level\":21,\"up\":true}"));
+	}
+}
@@ -1,6 +1,7 @@
 use crate::algebra::Point2f;
 use crate::difficulty_manager::DIFFICULTY_MULTIPLIER;
 use crate::graphic_object::{generate_thick_arc, GraphicObjectsIntoIter};
+use crate::palette;
 
 // this is used for visualize, calculation only works as effects
 pub struct StatusBar {
@@ -26,6 +27,12 @@ pub struct StatusBar {
 
 	split_angle: f32,
 
+	// rgb from palette
+	level_color: [f32; 3],
+	level_drop_color: [f32; 3],
+	quick_color: [f32; 3],
+	slow_color: [f32; 3],
+
 	pub score_update: bool,
 }
 
@@ -50,6 +57,11 @@ impl StatusBar {
 			rs_large,
 			split_angle: std::f32::consts::FRAC_PI_2,
 
+			level_color: palette::color("hud_level", [0.6, 0.9, 1.]),
+			level_drop_color: palette::color("hud_level_drop", [1., 0.4, 0.4]),
+			quick_color: palette::color("hud_quick", [0.6, 0.8, 0.3]),
+			slow_color: palette::color("hud_slow", [0.5, 0.4, 0.8]),
+
 			difficulty_percent,
 			difficulty_layer,
 			difficulty_last: difficulty_percent,
@@ -120,9 +132,9 @@ impl StatusBar {
 			(0., -&self.difficulty_percent * 2. * std::f32::consts::PI),
 			None,
 			Some([
-				0.6,
-				0.9,
-				1.0 - self.score_update as i32 as f32 * 0.5,
+				self.level_color[0],
+				self.level_color[1],
+				self.level_color[2] * (1.0 - self.score_update as i32 as f32 * 0.5),
 				0.3 + self.score_update as i32 as f32 * 0.2,
 			]),
 		);
@@ -135,7 +147,12 @@ impl StatusBar {
 					-&self.difficulty_early * 2. * std::f32::consts::PI,
 				),
 				None,
-				Some([1.0, 0.4, 0.4, 0.3]),
+				Some([
+					self.level_drop_color[0],
+					self.level_drop_color[1],
+					self.level_drop_color[2],
+					0.3,
+				]),
 			));
 		}
 		graphic_objects.extend(generate_thick_arc(
@@ -147,9 +164,9 @@ impl StatusBar {
 			),
 			None,
 			Some([
-				0.6,
-				0.8,
-				0.3,
+				self.quick_color[0],
+				self.quick_color[1],
+				self.quick_color[2],
 				if self.quick_percent > 0.99 { 0.2 } else { 0.4 },
 			]),
 		));
@@ -161,7 +178,12 @@ impl StatusBar {
 				self.split_angle + self.slow_percent * SLOW_SPLIT,
 			),
 			None,
-			Some([0.5, 0.4, 0.8, 0.4 + 0.2 * self.shift]),
+			Some([
+				self.slow_color[0],
+				self.slow_color[1],
+				self.slow_color[2],
+				0.4 + 0.2 * self.shift,
+			]),
 		));
 		graphic_objects.into_iter()
 	}
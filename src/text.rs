@@ -0,0 +1,47 @@
+use crate::algebra::Point2f;
+use crate::graphic_object::{GraphicObjects, Polygon2f};
+
+// draw a string with mray vector font, p is the left-up corner
+// each char takes a size * size box
+pub fn text_graphic_objects(
+	string: &str,
+	size: f32,
+	p: Point2f,
+	color: Option<[f32; 4]>,
+) -> GraphicObjects {
+	let mut graphic_objects: GraphicObjects = Default::default();
+	for (i, ch) in string.chars().enumerate() {
+		let char_objects = mray::fsd::fsd(ch)
+			.zoom(size)
+			.shift(p + Point2f::from_floats(i as f32 * size, 0.));
+		match color {
+			None => graphic_objects.extend(char_objects),
+			Some(color) => {
+				for graphic_object in char_objects.into_iter() {
+					if let Some(polygon) = graphic_object.as_any().downcast_ref::<Polygon2f>() {
+						graphic_objects.push(Box::new(Polygon2f {
+							vertices: polygon.vertices.clone(),
+							color,
+							border_color: color,
+						}));
+					} else {
+						graphic_objects.push(graphic_object);
+					}
+				}
+			}
+		}
+	}
+	graphic_objects
+}
+
+// horizontally centered in [0, width]
+pub fn centered_text_graphic_objects(
+	string: &str,
+	size: f32,
+	width: f32,
+	y: f32,
+	color: Option<[f32; 4]>,
+) -> GraphicObjects {
+	let x = (width - string.chars().count() as f32 * size) / 2.;
+	text_graphic_objects(string, size, Point2f::from_floats(x, y), color)
+}
@@ -1,3 +1,5 @@
+use crate::game_rules::GameRules;
+
 pub struct TimeManager {
 	dt_scaler: f32,
 	slowdown: bool,
@@ -10,15 +12,15 @@ pub struct TimeManager {
 }
 
 impl TimeManager {
-	pub fn new() -> TimeManager {
+	pub fn new(rules: &GameRules) -> TimeManager {
 		TimeManager {
 			dt_scaler: 1.,
 			slowdown: false,
 			shifting: false,
-			shift_rate_down: 2.5,
-			shift_rate_up: 5.,
+			shift_rate_down: rules.shift_rate_down,
+			shift_rate_up: rules.shift_rate_up,
 			dt_scaler_normal: 1.,
-			dt_scaler_slow: 0.5,
+			dt_scaler_slow: rules.dt_scaler_slow,
 		}
 	}
 
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+
+use crate::algebra::{Point2f, Rect2f};
+use crate::difficulty_manager::DIFFICULTY_MULTIPLIER;
+use crate::graphic_object::{GraphicObjects, LineSegs2f, Polygon2f};
+use crate::palette;
+use crate::text::text_graphic_objects;
+
+// seconds of unscaled time between points
+const SAMPLE_INTERVAL: f32 = 0.25;
+const LABEL_SIZE: f32 = 10.;
+
+// difficulty over unscaled session time, with hit markers,
+// saved with the record so replays can draw it without scanning ahead
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Timeline {
+	// time, difficulty
+	points: Vec<(f32, f32)>,
+	hits: Vec<f32>,
+	sample_timer: f32,
+}
+
+impl Timeline {
+	pub fn new() -> Timeline {
+		Default::default()
+	}
+
+	pub fn tick(&mut self, dt: f32, time: f32, difficulty: f32) {
+		self.sample_timer -= dt;
+		if self.sample_timer <= 0. {
+			self.sample_timer += SAMPLE_INTERVAL;
+			self.points.push((time, difficulty));
+		}
+	}
+
+	pub fn hit(&mut self, time: f32) {
+		self.hits.push(time);
+	}
+
+	pub fn length(&self) -> f32 {
+		self.points.last().map(|(time, _)| *time).unwrap_or(0.)
+	}
+
+	// time under x in graph drawn in rect
+	pub fn time_at(&self, rect: &Rect2f, x: f32) -> f32 {
+		((x - rect.lu.x) / rect.get_size().x).clamp(0., 1.) * self.length()
+	}
+
+	fn x(&self, rect: &Rect2f, time: f32) -> f32 {
+		let length = self.length();
+		if length <= 0. {
+			return rect.lu.x;
+		}
+		rect.lu.x + (time / length).min(1.) * rect.get_size().x
+	}
+
+	// y axis spans whole levels around the curve, cursor is a time
+	pub fn graphic_objects(&self, rect: &Rect2f, cursor: Option<f32>) -> GraphicObjects {
+		let label_color = palette::color_alpha("hud_text", [0.6, 0.6, 0.7, 0.7]);
+		let line_color = palette::color("hud_level", [0.6, 0.9, 1.]);
+		let hit_color = palette::color("hud_level_drop", [1., 0.4, 0.4]);
+		let mut graphic_objects: GraphicObjects = Default::default();
+		graphic_objects.push(Box::new(Polygon2f {
+			vertices: vec![
+				rect.lu,
+				Point2f::from_floats(rect.rd.x, rect.lu.y),
+				rect.rd,
+				Point2f::from_floats(rect.lu.x, rect.rd.y),
+			],
+			color: [0., 0., 0., 0.5],
+			border_color: label_color,
+		}));
+		if self.points.is_empty() {
+			return graphic_objects;
+		}
+
+		let (min, max) = self
+			.points
+			.iter()
+			.fold((f32::MAX, f32::MIN), |(min, max), (_, difficulty)| {
+				(min.min(*difficulty), max.max(*difficulty))
+			});
+		let low = (min * DIFFICULTY_MULTIPLIER).floor();
+		let high = (max * DIFFICULTY_MULTIPLIER).ceil().max(low + 1.);
+		let y = |difficulty: f32| {
+			rect.rd.y
+				- (difficulty * DIFFICULTY_MULTIPLIER - low) / (high - low) * rect.get_size().y
+		};
+
+		for time in self.hits.iter() {
+			let x = self.x(rect, *time);
+			graphic_objects.push(Box::new(LineSegs2f {
+				vertices: vec![
+					Point2f::from_floats(x, rect.lu.y),
+					Point2f::from_floats(x, rect.rd.y),
+				],
+				color: [hit_color[0], hit_color[1], hit_color[2], 0.6],
+			}));
+		}
+		// about one point per pixel is enough
+		let step = (self.points.len() / rect.get_size().x.max(1.) as usize).max(1);
+		let mut vertices: Vec<Point2f> = self
+			.points
+			.iter()
+			.step_by(step)
+			.map(|(time, difficulty)| Point2f::from_floats(self.x(rect, *time), y(*difficulty)))
+			.collect();
+		let (time, difficulty) = self.points[self.points.len() - 1];
+		vertices.push(Point2f::from_floats(self.x(rect, time), y(difficulty)));
+		graphic_objects.push(Box::new(LineSegs2f {
+			vertices,
+			color: [line_color[0], line_color[1], line_color[2], 1.],
+		}));
+		if let Some(time) = cursor {
+			let x = self.x(rect, time);
+			graphic_objects.push(Box::new(LineSegs2f {
+				vertices: vec![
+					Point2f::from_floats(x, rect.lu.y),
+					Point2f::from_floats(x, rect.rd.y),
+				],
+				color: palette::color_alpha("hud_highlight", [1., 1., 0.4, 1.]),
+			}));
+		}
+
+		for (level, label_y) in [(high, rect.lu.y + 2.), (low, rect.rd.y - LABEL_SIZE - 2.)].iter()
+		{
+			graphic_objects.extend(text_graphic_objects(
+				&(*level as u32).to_string(),
+				LABEL_SIZE,
+				Point2f::from_floats(rect.lu.x + 2., *label_y),
+				Some(label_color),
+			));
+		}
+		graphic_objects
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_timeline() {
+		let mut timeline = Timeline::new();
+		let mut time = 0.;
+		for _ in 0..60 {
+			time += 0.05;
+			timeline.tick(0.05, time, 0.2 + time * 0.01);
+		}
+		timeline.hit(1.);
+		// at 0.05, 0.3, 0.55 ...
+		assert_eq!(timeline.points.len(), 12);
+		assert!((timeline.length() - 2.8).abs() < 1e-4);
+		let rect = Rect2f::from_floats(10., 0., 110., 50.);
+		assert_eq!(timeline.time_at(&rect, 0.), 0.);
+		assert!((timeline.time_at(&rect, 60.) - 1.4).abs() < 1e-4);
+		assert_eq!(timeline.time_at(&rect, 200.), timeline.length());
+		assert!((timeline.x(&rect, 1.4) - 60.).abs() < 1e-4);
+	}
+}
@@ -0,0 +1,51 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+// 16 bit mono pcm
+pub fn write_wav<W: Write>(
+	writer: &mut W,
+	samples: &[f32],
+	sample_rate: u32,
+) -> std::io::Result<()> {
+	let data_len = samples.len() as u32 * 2;
+	writer.write_all(b"RIFF")?;
+	writer.write_all(&(36 + data_len).to_le_bytes())?;
+	writer.write_all(b"WAVEfmt ")?;
+	writer.write_all(&16u32.to_le_bytes())?;
+	// pcm, mono
+	writer.write_all(&1u16.to_le_bytes())?;
+	writer.write_all(&1u16.to_le_bytes())?;
+	writer.write_all(&sample_rate.to_le_bytes())?;
+	// byte rate, block align, bits per sample
+	writer.write_all(&(sample_rate * 2).to_le_bytes())?;
+	writer.write_all(&2u16.to_le_bytes())?;
+	writer.write_all(&16u16.to_le_bytes())?;
+	writer.write_all(b"data")?;
+	writer.write_all(&data_len.to_le_bytes())?;
+	for sample in samples.iter() {
+		let value = (sample.clamp(-1., 1.) * i16::MAX as f32) as i16;
+		writer.write_all(&value.to_le_bytes())?;
+	}
+	Ok(())
+}
+
+pub fn save_wav(filename: &str, samples: &[f32], sample_rate: u32) -> Result<(), String> {
+	let file = File::create(filename).map_err(|e| e.to_string())?;
+	let mut writer = BufWriter::new(file);
+	write_wav(&mut writer, samples, sample_rate).map_err(|e| e.to_string())?;
+	writer.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+	use super::write_wav;
+
+	#[test]
+	fn test_wav() {
+		let mut buffer = Vec::new();
+		write_wav(&mut buffer, &[0., 1., -2.], 8000).unwrap();
+		assert_eq!(buffer.len(), 44 + 6);
+		assert_eq!(&buffer[4..8], &42u32.to_le_bytes());
+		assert_eq!(&buffer[44..], &[0, 0, 0xff, 0x7f, 0x01, 0x80]);
+	}
+}
@@ -1,16 +1,21 @@
 use std::collections::VecDeque;
 
+use crate::cannon::CannonType;
 use crate::enemy::Enemy;
 
 use rand::Rng;
 use rand::SeedableRng;
 
+pub const WAVE_SCHEME_COUNT: u32 = 10;
+
+pub use wave_scheme_prototype::{compile_wave, compile_wave_file};
+
 mod wave_scheme_prototype {
 	use std::collections::VecDeque;
 
-	use super::CompiledWave;
+	use super::{CompiledWave, WAVE_SCHEME_COUNT};
 	use crate::cannon;
-	use crate::cannon::CannonControllerInterface;
+	use crate::cannon::{CannonControllerInterface, CannonType};
 	use crate::enemy::enemy_prototype;
 	use crate::enemy::Enemy;
 	use crate::enemy_path::{enemy_paths, EnemyPath};
@@ -20,6 +25,7 @@ mod wave_scheme_prototype {
 	use rand::seq::SliceRandom;
 	use rand::Rng;
 	use rand::SeedableRng;
+	use serde::Deserialize;
 
 	type GroupMemberSpatiotemporalInfo = Vec<(EnemyPath, Vec<f32>)>;
 	#[derive(Clone)]
@@ -120,7 +126,62 @@ mod wave_scheme_prototype {
 		};
 	}
 
+	// wave file is a toml like
+	// next_wave = 2.0
+	// [[enemies]]
+	// prototype = "small"
+	// [[enemies.groups]]
+	// path = "0.2 0 4 1 0.3 0.5 3 1 0 0.7 0 0"
+	// spawn = [0.5, 1.0, 1.5]
+	#[derive(Deserialize)]
+	struct WaveFile {
+		next_wave: f32,
+		#[serde(default = "default_difficulty_scaler")]
+		difficulty_scaler: f32,
+		enemies: Vec<WaveFileEnemy>,
+	}
+
+	fn default_difficulty_scaler() -> f32 {
+		1.
+	}
+
+	#[derive(Deserialize)]
+	struct WaveFileEnemy {
+		prototype: String,
+		groups: Vec<WaveFileGroup>,
+	}
+
+	#[derive(Deserialize)]
+	struct WaveFileGroup {
+		// in EnemyPath::from_str format
+		path: String,
+		spawn: Vec<f32>,
+	}
+
 	impl WaveSchemePrototype {
+		pub fn load(filename: &str) -> Result<WaveSchemePrototype, String> {
+			let string = std::fs::read_to_string(filename).map_err(|e| e.to_string())?;
+			let wave_file: WaveFile = toml::from_str(&string).map_err(|e| e.to_string())?;
+			let mut enemies = Vec::new();
+			for enemy in wave_file.enemies.into_iter() {
+				let prototype = enemy_prototype::from_name(&enemy.prototype)
+					.ok_or(format!("unknown enemy prototype {}", enemy.prototype))?;
+				enemies.push((
+					prototype,
+					enemy
+						.groups
+						.into_iter()
+						.map(|group| Ok((EnemyPath::try_from_str(&group.path)?, group.spawn)))
+						.collect::<Result<_, String>>()?,
+				));
+			}
+			Ok(WaveSchemePrototype {
+				enemies,
+				next_wave: wave_file.next_wave,
+				difficulty_scaler: wave_file.difficulty_scaler,
+			})
+		}
+
 		fn generate_wanderer1(seed: u64) -> WaveSchemePrototype {
 			let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(seed);
 			WaveSchemePrototype {
@@ -159,7 +220,12 @@ mod wave_scheme_prototype {
 			}
 		}
 
-		pub fn compile(&self, seed: u64, difficulty: f32) -> CompiledWave {
+		pub fn compile(
+			&self,
+			seed: u64,
+			difficulty: f32,
+			cannon_types: Option<&[CannonType]>,
+		) -> CompiledWave {
 			let mut enemies: Vec<(f32, Enemy)> = Vec::new();
 
 			let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(seed);
@@ -182,6 +248,7 @@ mod wave_scheme_prototype {
 						rng.gen::<u64>(),
 						difficulty * self.difficulty_scaler,
 						enemy_prototype.cannon_pool,
+						cannon_types,
 					);
 					for each_cannon_p in cannon_p_group {
 						let mut each_p_cannon = cannon_template.clone();
@@ -215,42 +282,79 @@ mod wave_scheme_prototype {
 		}
 	}
 
+	// waves and cannon_types restrict generated waves(for practice)
 	pub fn random_mapper(
 		seed: u64,
 		difficulty: f32,
 		last: Option<u32>,
+		waves: Option<&[u32]>,
+		cannon_types: Option<&[CannonType]>,
 	) -> (Option<u32>, CompiledWave) {
-		const SCHEME_SIZE: u32 = 10;
 		let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(seed);
-		let mut type_id = rng.gen_range(0, SCHEME_SIZE - 1);
-		// skip randomly generated path
-		if type_id != 0 {
-			if let Some(last) = last {
-				if type_id >= last {
-					type_id += 1;
-					type_id %= SCHEME_SIZE;
+		let type_id = match waves {
+			None => {
+				let mut type_id = rng.gen_range(0, WAVE_SCHEME_COUNT - 1);
+				// skip randomly generated path
+				if type_id != 0 {
+					if let Some(last) = last {
+						if type_id >= last {
+							type_id += 1;
+							type_id %= WAVE_SCHEME_COUNT;
+						}
+					}
 				}
+				type_id
 			}
-		}
+			Some(waves) => waves[rng.gen_range(0, waves.len())],
+		};
 		(
 			Some(type_id),
-			match type_id {
-				0 => WaveSchemePrototype::generate_wanderer1(rng.gen::<u64>())
-					.compile(rng.gen::<u64>(), difficulty),
-				1 => WaveSchemePrototype::generate_wanderer2(rng.gen::<u64>())
-					.compile(rng.gen::<u64>(), difficulty),
-				2 => LEFT_DOWN_CHAIN.compile(rng.gen::<u64>(), difficulty),
-				3 => RIGHT_DOWN_CHAIN.compile(rng.gen::<u64>(), difficulty),
-				4 => LEFT_RIGHT_CHAIN.compile(rng.gen::<u64>(), difficulty),
-				5 => RIGHT_LEFT_CHAIN.compile(rng.gen::<u64>(), difficulty),
-				6 => LEFT_RIGHT_MEDIUM.compile(rng.gen::<u64>(), difficulty),
-				7 => CLOCKWISE_CHAIN.compile(rng.gen::<u64>(), difficulty),
-				8 => COUNTERCLOCKWISE_CHAIN.compile(rng.gen::<u64>(), difficulty),
-				9 => MID_LARGE1.compile(rng.gen::<u64>(), difficulty),
-				_ => unreachable!(),
-			},
+			compile_by_id(type_id, &mut rng, difficulty, cannon_types),
 		)
 	}
+
+	// single wave of given scheme, for wave preview
+	pub fn compile_wave(type_id: u32, seed: u64, difficulty: f32) -> CompiledWave {
+		let mut rng = rand_pcg::Pcg64Mcg::seed_from_u64(seed);
+		compile_by_id(type_id, &mut rng, difficulty, None)
+	}
+
+	pub fn compile_wave_file(
+		filename: &str,
+		seed: u64,
+		difficulty: f32,
+	) -> Result<CompiledWave, String> {
+		Ok(WaveSchemePrototype::load(filename)?.compile(seed, difficulty, None))
+	}
+
+	fn compile_by_id(
+		type_id: u32,
+		rng: &mut rand_pcg::Pcg64Mcg,
+		difficulty: f32,
+		cannon_types: Option<&[CannonType]>,
+	) -> CompiledWave {
+		match type_id {
+			0 => WaveSchemePrototype::generate_wanderer1(rng.gen::<u64>()).compile(
+				rng.gen::<u64>(),
+				difficulty,
+				cannon_types,
+			),
+			1 => WaveSchemePrototype::generate_wanderer2(rng.gen::<u64>()).compile(
+				rng.gen::<u64>(),
+				difficulty,
+				cannon_types,
+			),
+			2 => LEFT_DOWN_CHAIN.compile(rng.gen::<u64>(), difficulty, cannon_types),
+			3 => RIGHT_DOWN_CHAIN.compile(rng.gen::<u64>(), difficulty, cannon_types),
+			4 => LEFT_RIGHT_CHAIN.compile(rng.gen::<u64>(), difficulty, cannon_types),
+			5 => RIGHT_LEFT_CHAIN.compile(rng.gen::<u64>(), difficulty, cannon_types),
+			6 => LEFT_RIGHT_MEDIUM.compile(rng.gen::<u64>(), difficulty, cannon_types),
+			7 => CLOCKWISE_CHAIN.compile(rng.gen::<u64>(), difficulty, cannon_types),
+			8 => COUNTERCLOCKWISE_CHAIN.compile(rng.gen::<u64>(), difficulty, cannon_types),
+			9 => MID_LARGE1.compile(rng.gen::<u64>(), difficulty, cannon_types),
+			_ => unreachable!(),
+		}
+	}
 }
 
 pub struct CompiledWave {
@@ -293,24 +397,38 @@ pub struct WaveGenerator {
 	wave_queue: VecDeque<CompiledWave>,
 
 	last_type: Option<u32>,
+	// scheme ids of waves started in last tick
+	spawned_waves: Vec<u32>,
+
+	// practice restrictions, None means all
+	waves: Option<Vec<u32>>,
+	cannon_types: Option<Vec<CannonType>>,
 
 	handle: Option<std::thread::JoinHandle<(Option<u32>, CompiledWave)>>,
 	//handle: Option<std::thread::JoinHandle<u32>>,
 }
 
 impl WaveGenerator {
-	pub fn new(seed: u64) -> WaveGenerator {
+	pub fn new(
+		seed: u64,
+		waves: Option<Vec<u32>>,
+		cannon_types: Option<Vec<CannonType>>,
+	) -> WaveGenerator {
 		WaveGenerator {
 			wave_cd: 1.,
 			rng: rand_pcg::Pcg64Mcg::seed_from_u64(seed),
 			wave_queue: VecDeque::new(),
 			last_type: None,
+			spawned_waves: Vec::new(),
+			waves,
+			cannon_types,
 			handle: None,
 		}
 	}
 
 	pub fn tick(&mut self, mut dt: f32, difficulty: f32) -> VecDeque<Enemy> {
 		let mut enemy_queue: VecDeque<Enemy> = VecDeque::new();
+		self.spawned_waves.clear();
 		// while is necessary, considering enemy generated at last frame and first frame
 		// may appear in one tick call
 
@@ -350,20 +468,39 @@ impl WaveGenerator {
 				let last_type = self.last_type;
 
 				let (last_type, compiled_wave) = if self.handle.is_none() {
-					println!("Hard wait on wave generation");
-					wave_scheme_prototype::random_mapper(seed, difficulty, last_type)
+					eprintln!("Hard wait on wave generation");
+					wave_scheme_prototype::random_mapper(
+						seed,
+						difficulty,
+						last_type,
+						self.waves.as_deref(),
+						self.cannon_types.as_deref(),
+					)
 				} else {
 					self.handle.take().unwrap().join().unwrap()
 				};
 				self.last_type = last_type;
+				self.spawned_waves.extend(last_type);
 				self.wave_queue.push_back(compiled_wave);
 				self.wave_cd = self.wave_queue.back().unwrap().next_wave * (1. - difficulty / 1.6);
 
+				let waves = self.waves.clone();
+				let cannon_types = self.cannon_types.clone();
 				self.handle = Some(std::thread::spawn(move || {
-					wave_scheme_prototype::random_mapper(seed, difficulty, last_type)
+					wave_scheme_prototype::random_mapper(
+						seed,
+						difficulty,
+						last_type,
+						waves.as_deref(),
+						cannon_types.as_deref(),
+					)
 				}));
 			}
 		}
 		enemy_queue
 	}
+
+	pub fn get_spawned_waves(&self) -> &[u32] {
+		&self.spawned_waves
+	}
 }
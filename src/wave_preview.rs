@@ -0,0 +1,204 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use crate::algebra::Point2f;
+use crate::bullet_pool::BulletPool;
+use crate::canvas::Canvas;
+use crate::clip::clip;
+use crate::collision::CollisionPipeInterface;
+use crate::enemy_pool::EnemyPool;
+use crate::graphic_object::{generate_thick_arc, GraphicObjects};
+use crate::png_writer::save_png;
+use crate::wave_generator::{compile_wave, compile_wave_file, CompiledWave, WAVE_SCHEME_COUNT};
+use crate::window_rect::{parse_point, WINDOW_RECT, WINDOW_SIZE};
+
+// simulation step, independent of output frame rate
+const DT: f32 = 1. / 120.;
+
+pub fn subcommand() -> App<'static, 'static> {
+	SubCommand::with_name("preview")
+		.about("render a compiled wave to png without playing")
+		.arg(
+			Arg::with_name("wave")
+				.required(true)
+				.help("wave scheme id or wave file"),
+		)
+		.arg(
+			Arg::with_name("seed")
+				.short("s")
+				.long("seed")
+				.takes_value(true)
+				.help("random seed used, default 0"),
+		)
+		.arg(
+			Arg::with_name("difficulty")
+				.short("d")
+				.long("difficulty")
+				.takes_value(true)
+				.help("difficulty of wave, default 0.5"),
+		)
+		.arg(
+			Arg::with_name("out")
+				.short("o")
+				.long("out")
+				.takes_value(true)
+				.help("output png, or output directory with --sequence, default wave.png"),
+		)
+		.arg(
+			Arg::with_name("sequence")
+				.long("sequence")
+				.help("write an image sequence instead of a long exposure"),
+		)
+		.arg(
+			Arg::with_name("fps")
+				.long("fps")
+				.takes_value(true)
+				.help("frames per second sampled, default 30"),
+		)
+		.arg(
+			Arg::with_name("duration")
+				.long("duration")
+				.takes_value(true)
+				.help("max simulated seconds, default 20"),
+		)
+		.arg(
+			Arg::with_name("player")
+				.long("player")
+				.takes_value(true)
+				.help("dummy player position, default \"250,600\""),
+		)
+}
+
+pub fn run(matches: &ArgMatches) {
+	let seed = matches
+		.value_of("seed")
+		.map(|seed| seed.parse::<u64>().unwrap())
+		.unwrap_or(0);
+	let difficulty = matches
+		.value_of("difficulty")
+		.map(|difficulty| difficulty.parse::<f32>().unwrap())
+		.unwrap_or(0.5);
+	let wave_arg = matches.value_of("wave").unwrap();
+	let wave = match wave_arg.parse::<u32>() {
+		Ok(id) if id < WAVE_SCHEME_COUNT => compile_wave(id, seed, difficulty),
+		Ok(id) => panic!(
+			"Wave scheme id should be less than {}, got {}",
+			WAVE_SCHEME_COUNT, id
+		),
+		Err(_) => compile_wave_file(wave_arg, seed, difficulty)
+			.unwrap_or_else(|e| panic!("Failed to load wave file {}: {}", wave_arg, e)),
+	};
+	let fps = matches
+		.value_of("fps")
+		.map(|fps| fps.parse::<f32>().unwrap())
+		.unwrap_or(30.);
+	let duration = matches
+		.value_of("duration")
+		.map(|duration| duration.parse::<f32>().unwrap())
+		.unwrap_or(20.);
+	let player_p = match matches.value_of("player") {
+		None => Point2f::from_floats(250., 600.),
+		Some(player) => parse_point(player).unwrap_or_else(|e| panic!("Invalid --player: {}", e)),
+	};
+	let sequence = matches.is_present("sequence");
+	let out = matches
+		.value_of("out")
+		.unwrap_or(if sequence { "wave" } else { "wave.png" });
+	if sequence {
+		std::fs::create_dir_all(out).unwrap();
+	}
+	let frames = preview(
+		wave,
+		player_p,
+		fps,
+		duration,
+		sequence,
+		|frame, size, data| {
+			let filename = if sequence {
+				format!("{}/{:05}.png", out, frame)
+			} else {
+				out.to_string()
+			};
+			save_png(&filename, size, data)
+				.unwrap_or_else(|e| panic!("Failed to write {}: {}", filename, e));
+		},
+	);
+	println!("{} frames simulated", frames);
+}
+
+// simulate until wave is cleared or duration ends,
+// in long exposure mode frames are drawn over each other and output once
+// return number of frames sampled
+fn preview<F>(
+	mut wave: CompiledWave,
+	player_p: Point2f,
+	fps: f32,
+	duration: f32,
+	sequence: bool,
+	mut output: F,
+) -> u32
+where
+	F: FnMut(u32, (u32, u32), &[u8]),
+{
+	let size = (WINDOW_SIZE.x as u32, WINDOW_SIZE.y as u32);
+	let mut canvas = Canvas::new((size.0 as i32, size.1 as i32), 1.);
+	canvas.flush();
+	let mut enemy_pool = EnemyPool::new();
+	let mut enemy_bullet_pool = BulletPool::new();
+	let mut wave_finished = false;
+	let mut timer = 0.;
+	let mut frame_timer = 0.;
+	let mut frame = 0;
+	while timer < duration {
+		match wave.tick(DT) {
+			None => wave_finished = true,
+			Some(enemy_queue) => enemy_pool.extend(enemy_queue),
+		}
+		enemy_bullet_pool.tick(DT);
+		enemy_bullet_pool.extend(enemy_pool.tick(DT, player_p));
+		timer += DT;
+		if wave_finished && enemy_pool.len() == 0 && enemy_bullet_pool.len() == 0 {
+			break;
+		}
+
+		frame_timer += DT;
+		if frame_timer < 1. / fps {
+			continue;
+		}
+		frame_timer -= 1. / fps;
+		if sequence {
+			canvas.flush();
+		}
+		for graphic_object in enemy_pool
+			.graphic_objects_iter()
+			.chain(enemy_bullet_pool.graphic_objects_iter())
+		{
+			for clipped in clip(graphic_object, &WINDOW_RECT) {
+				clipped.render(&mut canvas);
+			}
+		}
+		if sequence {
+			for graphic_object in dummy_player(player_p).into_iter() {
+				graphic_object.render(&mut canvas);
+			}
+			output(frame, size, &canvas.data);
+		}
+		frame += 1;
+	}
+	if !sequence {
+		for graphic_object in dummy_player(player_p).into_iter() {
+			graphic_object.render(&mut canvas);
+		}
+		output(0, size, &canvas.data);
+	}
+	frame
+}
+
+fn dummy_player(player_p: Point2f) -> GraphicObjects {
+	generate_thick_arc(
+		player_p,
+		(6., 8.),
+		(0., 2. * std::f32::consts::PI),
+		Some([1., 0.3, 0.3, 1.]),
+		Some([1., 0.3, 0.3, 0.3]),
+	)
+}
@@ -4,9 +4,57 @@ use lazy_static::lazy_static;
 lazy_static! {
 	pub static ref WINDOW_RECT: Rect2f = Rect2f::from_floats(0., 0., 500., 700.);
 	pub static ref WINDOW_SIZE: Point2f = WINDOW_RECT.get_size();
-	pub static ref SCALER: f32 = match std::env::var("SCALER") {
-		Ok(val) => val.parse::<f32>().unwrap(),
-		_ => 1.,
-	};
-	pub static ref WINDOW_SIZE_SCALED: Point2f = WINDOW_RECT.get_size() * *SCALER;
+	// optional side panel, right of the playfield
+	pub static ref PANEL_RECT: Rect2f = Rect2f::from_floats(500., 0., 700., 700.);
+	// difficulty graph of replay at the bottom of playfield, doubles as scrub bar
+	pub static ref TIMELINE_RECT: Rect2f = Rect2f::from_floats(10., 650., 490., 690.);
+}
+
+// logical size of displayed frame
+pub fn frame_size(panel: bool) -> Point2f {
+	if panel {
+		Point2f::from_floats(PANEL_RECT.rd.x, WINDOW_SIZE.y)
+	} else {
+		*WINDOW_SIZE
+	}
+}
+
+// "x,y", e.g. "250,200"
+pub fn parse_point(arg: &str) -> Result<Point2f, String> {
+	let xy = arg
+		.split(',')
+		.map(|x| x.trim().parse::<f32>().map_err(|e| e.to_string()))
+		.collect::<Result<Vec<f32>, String>>()?;
+	if xy.len() != 2 {
+		return Err(format!("expected \"x,y\", got \"{}\"", arg));
+	}
+	Ok(Point2f::from_floats(xy[0], xy[1]))
+}
+
+// largest scaler to fit the frame in a window,
+// in quarter steps so that scaled canvas size is integral
+pub fn fit_scaler(window_size: (u32, u32), frame_size: Point2f) -> f32 {
+	let scaler = (window_size.0 as f32 / frame_size.x).min(window_size.1 as f32 / frame_size.y);
+	((scaler * 4.).floor() / 4.).max(0.25)
+}
+
+pub fn scaled_size(scaler: f32, frame_size: Point2f) -> (u32, u32) {
+	(
+		(frame_size.x * scaler) as u32,
+		(frame_size.y * scaler) as u32,
+	)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_parse_point() {
+		let p = parse_point("250, 200").unwrap();
+		assert_eq!((p.x, p.y), (250., 200.));
+		assert!(parse_point("100").is_err());
+		assert!(parse_point("1,2,3").is_err());
+		assert!(parse_point("a,2").is_err());
+	}
 }